@@ -1,13 +1,31 @@
 use std::ops::Add;
+use std::ops::Sub;
 use std::ops::Neg;
 use std::ops::AddAssign;
+use std::ops::SubAssign;
 use std::cmp::PartialEq;
 use std::cmp::{Ordering, PartialOrd};
 use std::collections::HashMap;
 use std::ops::Index;
 use std::ops::IndexMut;
+use std::ops::Mul;
+use std::fmt;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::ops::Div;
+use std::str::FromStr;
+use std::iter::Sum;
+use std::iter::Product;
+use std::ops::BitAnd;
+use std::ops::MulAssign;
+use std::ops::DivAssign;
+use std::f64::consts::PI;
+use std::ops::Rem;
+use std::ops::BitOr;
+use std::convert::TryFrom;
+use std::ops::RemAssign;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct Complex<T> {
     pub re: T,
     pub im: T,
@@ -35,6 +53,29 @@ impl<L, R, O> Add<Complex<R>> for Complex<L>
     }
 }
 
+// 参照同士の「+」のオーバーロード
+// 標準ライブラリの数値型と同様に&a + &bを実装しておくと、成分がCopyでない型でも
+// 値を移動させずに加算できる
+impl<'a, 'b, L, R, O> Add<&'b Complex<R>> for &'a Complex<L>
+    where &'a L: Add<&'b R, Output=O>
+{
+    type Output = Complex<O>;
+    fn add(self, rhs: &'b Complex<R>) -> Self::Output {
+        Complex { re: &self.re + &rhs.re, im: &self.im + &rhs.im }
+    }
+}
+
+// 算術演算子「-」のオーバーロード
+// Addと同様にL, R, Oを分けた最大限ジェネリックな実装
+impl<L, R, O> Sub<Complex<R>> for Complex<L>
+    where L: Sub<R, Output=O>
+{
+    type Output = Complex<O>;
+    fn sub(self, rhs: Complex<R>) -> Self::Output {
+        Complex { re: self.re - rhs.re, im: self.im - rhs.im }
+    }
+}
+
 // 単項演算子「-」のオーバーロード
 impl<T, O> Neg for Complex<T>
     where T: Neg<Output=O>
@@ -55,32 +96,981 @@ impl<T> AddAssign for Complex<T>
     }
 }
 
-// std::cmp::PartialEqのeqメソッドとneメソッドのうち、neはデフォルト実装を持つ
-// よってeqメソッドのみ実装することでComplex型は「==」で比較可能になる
-impl<T: PartialEq> PartialEq for Complex<T> {
-    fn eq(&self, other: &Complex<T>) -> bool {
-        self.re == other.re && self.im == other.im
+// 実数を足す「+=」は実部だけを変更する
+impl<T: AddAssign<T>> AddAssign<T> for Complex<T> {
+    fn add_assign(&mut self, rhs: T) {
+        self.re += rhs;
+    }
+}
+
+// 複合代入演算子「-=」のオーバーロード
+impl<T> SubAssign for Complex<T>
+    where T: SubAssign<T>
+{
+    fn sub_assign(&mut self, rhs: Complex<T>) {
+        self.re -= rhs.re;
+        self.im -= rhs.im;
+    }
+}
+
+// 実数を引く「-=」は実部だけを変更する
+impl<T: SubAssign<T>> SubAssign<T> for Complex<T> {
+    fn sub_assign(&mut self, rhs: T) {
+        self.re -= rhs;
+    }
+}
+
+// 複合代入演算子「*=」「/=」のオーバーロード
+// 実部と虚部の計算に互いの元の値が必要なため、成分ごとに更新せずMul/Divの結果で置き換える
+impl MulAssign for Complex<f64> {
+    fn mul_assign(&mut self, rhs: Complex<f64>) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Complex<f64> {
+    fn div_assign(&mut self, rhs: Complex<f64>) {
+        *self = *self / rhs;
+    }
+}
+
+// 実数を掛ける「*=」は両方の成分を定数倍する
+impl<T: MulAssign<T> + Copy> MulAssign<T> for Complex<T> {
+    fn mul_assign(&mut self, rhs: T) {
+        self.re *= rhs;
+        self.im *= rhs;
+    }
+}
+
+// std::cmp::PartialEqのeqメソッドとneメソッドのうち、neはデフォルト実装を持つ
+// よってeqメソッドのみ実装することでComplex型は「==」で比較可能になる
+impl<T: PartialEq> PartialEq for Complex<T> {
+    fn eq(&self, other: &Complex<T>) -> bool {
+        self.re == other.re && self.im == other.im
+    }
+}
+
+// 成分の型がEqであれば、フィールドごとの比較は完全同値関係になる
+impl<T: Eq> Eq for Complex<T> {}
+
+// HashSetやHashMapのキーにするにはEqとHashの両方が必要
+// a == bならばhash(a) == hash(b)となるよう、eqと同じくre、imの順にハッシュする
+// f64はHashを実装していないため、Complex<f64>はこの実装の対象にならずキーにできない
+impl<T: Hash> Hash for Complex<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.re.hash(state);
+        self.im.hash(state);
+    }
+}
+
+// 算術演算子「*」のオーバーロード
+// (a + bi)(c + di) = (ac - bd) + (ad + bc)i
+// 各フィールドを2回ずつ使うため、TにCopy制約が必要になる
+impl<T> Mul for Complex<T>
+    where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Copy
+{
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        Complex {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re
+        }
+    }
+}
+
+// 実数のスカラー倍 (a + bi) * k = ak + bki
+// 右辺がComplex<T>ではなく裸のTなので、上のMul for Complex<T>とは衝突しない
+// rhsをreとimの両方に掛けるため、TにCopy制約が必要になる
+impl<T> Mul<T> for Complex<T>
+    where T: Mul<Output=T> + Copy
+{
+    type Output = Complex<T>;
+    fn mul(self, rhs: T) -> Complex<T> {
+        Complex { re: self.re * rhs, im: self.im * rhs }
+    }
+}
+
+// 左辺がスカラー値の「*」 k * (a + bi)
+// 孤児ルールによりimpl<T> Mul<Complex<T>> for Tのようなジェネリックな実装は書けないため、
+// 型ごとに具体的に実装する
+impl Mul<Complex<f64>> for f64 {
+    type Output = Complex<f64>;
+    fn mul(self, rhs: Complex<f64>) -> Complex<f64> {
+        rhs * self
+    }
+}
+
+impl Mul<Complex<i32>> for i32 {
+    type Output = Complex<i32>;
+    fn mul(self, rhs: Complex<i32>) -> Complex<i32> {
+        rhs * self
+    }
+}
+
+// 算術演算子「/」のオーバーロード
+// (a + bi) / (c + di) = ((ac + bd) + (bc - ad)i) / (c^2 + d^2)
+// 整数型では割り算の切り捨てで結果が変わってしまうため、浮動小数点型に限定して実装する
+impl Div for Complex<f64> {
+    type Output = Complex<f64>;
+    fn div(self, rhs: Complex<f64>) -> Complex<f64> {
+        let denom = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex {
+            re: (self.re * rhs.re + self.im * rhs.im) / denom,
+            im: (self.im * rhs.re - self.re * rhs.im) / denom
+        }
+    }
+}
+
+impl Div for Complex<f32> {
+    type Output = Complex<f32>;
+    fn div(self, rhs: Complex<f32>) -> Complex<f32> {
+        let denom = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex {
+            re: (self.re * rhs.re + self.im * rhs.im) / denom,
+            im: (self.im * rhs.re - self.re * rhs.im) / denom
+        }
+    }
+}
+
+// 実数で割る「/」は両方の成分をそのまま割る
+// 複素数同士の割り算と違いノルムを計算しないが、成分が整数型の場合は
+// 整数の割り算と同じく0方向に切り捨てられる
+impl<T: Div<Output=T> + Copy> Div<T> for Complex<T> {
+    type Output = Complex<T>;
+    fn div(self, rhs: T) -> Complex<T> {
+        Complex { re: self.re / rhs, im: self.im / rhs }
+    }
+}
+
+impl<T> Complex<T> {
+    // 共役複素数 a + biに対してa - biを返す
+    pub fn conjugate(self) -> Complex<T>
+        where T: Neg<Output=T>
+    {
+        Complex { re: self.re, im: -self.im }
+    }
+
+    // 絶対値の2乗 re^2 + im^2
+    // sqrtを必要としないため、整数型の成分でも計算できる
+    pub fn norm_sqr(self) -> T
+        where T: Add<Output=T> + Mul<Output=T> + Copy
+    {
+        self.re * self.re + self.im * self.im
+    }
+
+    // 両成分をfactor倍する
+    pub fn scale(self, factor: T) -> Complex<T>
+        where T: Mul<Output=T> + Copy
+    {
+        Complex { re: self.re * factor, im: self.im * factor }
+    }
+
+    // 両成分をfactorで割る
+    pub fn unscale(self, factor: T) -> Complex<T>
+        where T: Div<Output=T> + Copy
+    {
+        Complex { re: self.re / factor, im: self.im / factor }
+    }
+
+    // 各成分をFromで変換し、成分の型が異なる複素数にする
+    pub fn cast<U: From<T>>(self) -> Complex<U> {
+        self.map(U::from)
+    }
+
+    // 実部と虚部の両方にfを適用する
+    pub fn map<U, F: Fn(T) -> U>(self, f: F) -> Complex<U> {
+        Complex { re: f(self.re), im: f(self.im) }
+    }
+}
+
+// 絶対値の計算にはsqrtが必要なため、浮動小数点型に限定して実装する
+impl Complex<f64> {
+    // 加法の単位元 0 + 0i
+    pub fn zero() -> Complex<f64> {
+        Complex { re: 0.0, im: 0.0 }
+    }
+
+    // 乗法の単位元 1 + 0i
+    pub fn one() -> Complex<f64> {
+        Complex { re: 1.0, im: 0.0 }
+    }
+
+    // 虚数単位 i
+    pub fn i() -> Complex<f64> {
+        Complex { re: 0.0, im: 1.0 }
+    }
+
+    pub fn norm(self) -> f64 {
+        self.norm_sqr().sqrt()
+    }
+
+    // 複素平面上の点とみなした2点間のユークリッド距離
+    pub fn dist(self, other: Complex<f64>) -> f64 {
+        (self - other).norm()
+    }
+
+    // 偏角(ラジアン)。atan2により-πからπの範囲になる
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    // 極形式(絶対値, 偏角)への変換
+    pub fn to_polar(self) -> (f64, f64) {
+        (self.norm(), self.arg())
+    }
+
+    // 極形式からの変換 r(cosθ + i sinθ)
+    pub fn from_polar(r: f64, theta: f64) -> Complex<f64> {
+        Complex { re: r * theta.cos(), im: r * theta.sin() }
+    }
+
+    // 浮動小数点数の==は厳密な比較になるため、実部と虚部の差がそれぞれeps以内かで比較する
+    pub fn approx_eq(self, other: Complex<f64>, eps: f64) -> bool {
+        (self.re - other.re).abs() <= eps && (self.im - other.im).abs() <= eps
+    }
+
+    // 整数乗。繰り返し二乗法により、乗算の回数はnのビット数程度で済む
+    // z^0 = 1 + 0i、負の指数は逆数 1 / z^|n| として計算する
+    pub fn powi(self, n: i32) -> Complex<f64> {
+        let mut base = self;
+        let mut exp = n.unsigned_abs();
+        let mut result = Complex::one();
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exp >>= 1;
+        }
+        if n < 0 { Complex::one() / result } else { result }
+    }
+
+    // 指数関数 e^(a + bi) = e^a (cos b + i sin b)
+    // 絶対値e^a、偏角bの極形式と同じ
+    pub fn exp(self) -> Complex<f64> {
+        Complex::from_polar(self.re.exp(), self.im)
+    }
+
+    // 主値の平方根。極形式 r e^(iθ) に対して √r e^(iθ/2) を返す
+    // 偏角θは-πからπの範囲なので、θ/2は-π/2からπ/2となり実部は負にならない
+    pub fn sqrt(self) -> Complex<f64> {
+        let (r, theta) = self.to_polar();
+        Complex::from_polar(r.sqrt(), theta / 2.0)
+    }
+
+    // selfからotherへの線形補間。t = 0でself、t = 1でotherになる
+    // tを[0, 1]に制限しないため、範囲外のtでは2点を通る直線上に外挿される
+    pub fn lerp(self, other: Complex<f64>, t: f64) -> Complex<f64> {
+        self * (1.0 - t) + other * t
+    }
+
+    // 主値の自然対数 ln z = ln|z| + i arg z
+    // 偏角をatan2で-πからπの範囲にとるため、虚部も-πからπの範囲になる
+    // 0の対数は実部が負の無限大になる
+    pub fn ln(self) -> Complex<f64> {
+        Complex { re: self.norm().ln(), im: self.arg() }
+    }
+
+    // 複素数乗 z^w = e^(w ln z)。lnの主値を使うため、結果も主値になる
+    pub fn powc(self, exponent: Complex<f64>) -> Complex<f64> {
+        (exponent * self.ln()).exp()
+    }
+
+    // 逆数 1 / z = conj(z) / |z|^2
+    pub fn recip(self) -> Complex<f64> {
+        self.conjugate().scale(1.0 / self.norm_sqr())
+    }
+
+    // 両方の成分が有限の値(NaNでも無限大でもない)かどうか
+    pub fn is_finite(self) -> bool {
+        self.re.is_finite() && self.im.is_finite()
+    }
+
+    // どちらかの成分がNaNかどうか
+    // NaNはどの値とも等しくないため、NaNを含む複素数は自分自身とも==で等しくならない
+    pub fn is_nan(self) -> bool {
+        self.re.is_nan() || self.im.is_nan()
+    }
+
+    // 数学ライブラリに合わせたnormの別名
+    pub fn abs(self) -> f64 {
+        self.norm()
+    }
+}
+
+impl Complex<f32> {
+    pub fn norm(self) -> f32 {
+        self.norm_sqr().sqrt()
+    }
+
+    pub fn abs(self) -> f32 {
+        self.norm()
+    }
+}
+
+// {}で「3+4i」のように表示するためのDisplayの実装
+// 各成分は成分の型のDisplayに委譲するので、{:.2}などの精度指定も成分ごとに反映される
+// 虚部は精度指定だけを反映して一旦文字列にし、先頭が「-」でなければ「+」を補う
+// そのため-0.0は「3-0i」となり、{:+}の符号指定は実部にだけ反映されて「+3+4i」となる
+// 幅や埋め文字、0埋めは「3-4i」全体に対して適用する。Formatter::padは精度を最大文字数として
+// 切り詰めてしまうため使わず、数値と同じく既定では右寄せ、0埋めでは先頭の符号の後ろに0を入れる
+impl<T: fmt::Display> fmt::Display for Complex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let re = match (f.precision(), f.sign_plus()) {
+            (Some(precision), true) => format!("{:+.*}", precision, self.re),
+            (Some(precision), false) => format!("{:.*}", precision, self.re),
+            (None, true) => format!("{:+}", self.re),
+            (None, false) => format!("{}", self.re)
+        };
+        let im = match f.precision() {
+            Some(precision) => format!("{:.*}", precision, self.im),
+            None => format!("{}", self.im)
+        };
+        let sign = if im.starts_with('-') { "" } else { "+" };
+        let s = format!("{}{}{}i", re, sign, im);
+
+        let pad = f.width().unwrap_or(0).saturating_sub(s.chars().count());
+        if pad == 0 {
+            return f.write_str(&s);
+        }
+        if f.sign_aware_zero_pad() {
+            let split = if s.starts_with('+') || s.starts_with('-') { 1 } else { 0 };
+            f.write_str(&s[..split])?;
+            f.write_str(&"0".repeat(pad))?;
+            return f.write_str(&s[split..]);
+        }
+        let (before, after) = match f.align() {
+            Some(fmt::Alignment::Left) => (0, pad),
+            Some(fmt::Alignment::Center) => (pad / 2, pad - pad / 2),
+            _ => (pad, 0)
+        };
+        let fill = f.fill().to_string();
+        f.write_str(&fill.repeat(before))?;
+        f.write_str(&s)?;
+        f.write_str(&fill.repeat(after))
+    }
+}
+
+// スカラー値は虚部が0の実数として変換する
+impl<T: Default> From<T> for Complex<T> {
+    fn from(re: T) -> Complex<T> {
+        Complex { re, im: T::default() }
+    }
+}
+
+// タプル(re, im)からの変換
+impl<T> From<(T, T)> for Complex<T> {
+    fn from((re, im): (T, T)) -> Complex<T> {
+        Complex { re, im }
+    }
+}
+
+// タプル(re, im)への変換
+impl<T> From<Complex<T>> for (T, T) {
+    fn from(c: Complex<T>) -> (T, T) {
+        (c.re, c.im)
+    }
+}
+
+// c[0]で実部、c[1]で虚部を参照できるようにするIndex/IndexMutの実装
+// 2要素のベクトルとみなすため、それ以外の添字ではパニックする
+impl<T> Index<usize> for Complex<T> {
+    type Output = T;
+    fn index(&self, index: usize) -> &T {
+        match index {
+            0 => &self.re,
+            1 => &self.im,
+            _ => panic!("Complex index out of range: {}", index)
+        }
+    }
+}
+
+impl<T> IndexMut<usize> for Complex<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        match index {
+            0 => &mut self.re,
+            1 => &mut self.im,
+            _ => panic!("Complex index out of range: {}", index)
+        }
+    }
+}
+
+// 文字列からComplex<f64>へのパースに失敗した理由
+#[derive(Debug, PartialEq)]
+pub enum ParseComplexError {
+    Empty,
+    MissingImaginaryUnit,
+    InvalidNumber(String)
+}
+
+impl fmt::Display for ParseComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseComplexError::Empty => write!(f, "cannot parse complex from empty string"),
+            ParseComplexError::MissingImaginaryUnit => write!(f, "imaginary part must end with 'i'"),
+            ParseComplexError::InvalidNumber(s) => write!(f, "invalid number: {:?}", s)
+        }
+    }
+}
+
+impl std::error::Error for ParseComplexError {}
+
+fn parse_component(s: &str) -> Result<f64, ParseComplexError> {
+    s.parse().map_err(|_| ParseComplexError::InvalidNumber(s.to_string()))
+}
+
+// 「i」「-i」のように虚部の係数が省略された場合は1として扱う
+fn parse_imaginary(s: &str) -> Result<f64, ParseComplexError> {
+    match s {
+        "" | "+" => Ok(1.0),
+        "-" => Ok(-1.0),
+        _ => parse_component(s)
+    }
+}
+
+// bytes[i]が項を区切る符号かどうか。「1e-3」のような指数表記の符号は区切りではない
+fn is_term_sign(bytes: &[u8], i: usize) -> bool {
+    (bytes[i] == b'+' || bytes[i] == b'-') && bytes[i - 1] != b'e' && bytes[i - 1] != b'E'
+}
+
+// 「- 3」のように先頭の符号と数値の間にある空白を取り除く
+fn strip_sign_space(s: &str) -> String {
+    let s = s.trim();
+    if s.starts_with('+') || s.starts_with('-') {
+        format!("{}{}", &s[..1], s[1..].trim())
+    } else {
+        s.to_string()
+    }
+}
+
+// 「3+4i」「-2-5i」「7」「4i」のような文字列をパースするFromStrの実装
+// FromStrを実装すると"3+4i".parse::<Complex<f64>>()のようにも書ける
+impl FromStr for Complex<f64> {
+    type Err = ParseComplexError;
+    fn from_str(s: &str) -> Result<Complex<f64>, ParseComplexError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseComplexError::Empty);
+        }
+        // 先頭以外にある最後の符号を実部と虚部の区切りとみなす
+        let bytes = s.as_bytes();
+        let split = (1..bytes.len()).rev().find(|&i| is_term_sign(bytes, i));
+        match split {
+            Some(i) => {
+                let (re, im) = s.split_at(i);
+                if !im.ends_with('i') {
+                    return Err(ParseComplexError::MissingImaginaryUnit);
+                }
+                // 符号と係数の間の空白を取り除く
+                let im = format!("{}{}", &im[..1], im[1..im.len() - 1].trim());
+                Ok(Complex { re: parse_component(&strip_sign_space(re))?, im: parse_imaginary(&im)? })
+            },
+            None if s.ends_with('i') => {
+                Ok(Complex { re: 0.0, im: parse_imaginary(&strip_sign_space(&s[..s.len() - 1]))? })
+            },
+            None => Ok(Complex { re: parse_component(&strip_sign_space(s))?, im: 0.0 })
+        }
+    }
+}
+
+// 「3+4i - 1 + 2i」のように実数と虚数の項を「+」「-」でつないだ式を評価する
+// 式を符号の位置で項に分け、それぞれをFromStrでパースしてからSumで足し合わせる
+fn eval(expr: &str) -> Result<Complex<f64>, ParseComplexError> {
+    let expr = expr.trim();
+    if expr.is_empty() {
+        return Err(ParseComplexError::Empty);
+    }
+    let bytes = expr.as_bytes();
+    let mut terms = Vec::new();
+    let mut start = 0;
+    for i in 1..bytes.len() {
+        if is_term_sign(bytes, i) {
+            terms.push(&expr[start..i]);
+            start = i;
+        }
+    }
+    terms.push(&expr[start..]);
+    terms.into_iter().map(|term| {
+        // 符号と数値の間の空白を取り除いてからパースする
+        let term = term.trim();
+        match term.as_bytes()[0] {
+            b'+' | b'-' => format!("{}{}", &term[..1], term[1..].trim()).parse(),
+            _ => term.parse()
+        }
+    }).sum()
+}
+
+// Sumを実装するとイテレータのsum()で総和を求められる
+// 空のイテレータの総和は0 + 0iになる
+impl<T: Add<Output=T> + Default> Sum for Complex<T> {
+    fn sum<I: Iterator<Item=Complex<T>>>(iter: I) -> Complex<T> {
+        iter.fold(Complex::default(), |acc, c| acc + c)
+    }
+}
+
+// Productを実装するとイテレータのproduct()で総乗を求められる
+// 空のイテレータの総乗は乗法の単位元1 + 0iになる
+impl Product for Complex<f64> {
+    fn product<I: Iterator<Item=Complex<f64>>>(iter: I) -> Complex<f64> {
+        iter.fold(Complex::one(), |acc, c| acc * c)
+    }
+}
+
+// エルミート内積 Σ conj(a[i]) * b[i]
+// 自分自身との内積は各要素の絶対値の2乗の和になり、虚部は0になる
+fn hermitian_dot(a: &[Complex<f64>], b: &[Complex<f64>]) -> Complex<f64> {
+    assert_eq!(a.len(), b.len(), "hermitian_dot requires slices of equal length");
+    a.iter().zip(b).map(|(x, y)| x.conjugate() * *y).sum()
+}
+
+// 成分が整数の複素数(ガウス整数)の演算
+impl Complex<i32> {
+    // 商がガウス整数として割り切れる場合のみSomeを返す割り算
+    // 途中の積の和はi64にも収まらない場合がある(c = d = i32::MINでc * c + d * d = 2^63)ため、i128で計算する
+    pub fn checked_div(self, rhs: Complex<i32>) -> Option<Complex<i32>> {
+        let (a, b) = (i128::from(self.re), i128::from(self.im));
+        let (c, d) = (i128::from(rhs.re), i128::from(rhs.im));
+        let denom = c * c + d * d;
+        if denom == 0 {
+            return None;
+        }
+        let (re, im) = (a * c + b * d, b * c - a * d);
+        if re % denom != 0 || im % denom != 0 {
+            return None;
+        }
+        Some(Complex { re: i32::try_from(re / denom).ok()?, im: i32::try_from(im / denom).ok()? })
+    }
+}
+
+// n / dを最も近い整数に丸める(d > 0)。ちょうど中間の値は正の無限大の方向に丸める
+fn round_div(n: i128, d: i128) -> i128 {
+    (2 * n + d).div_euclid(2 * d)
+}
+
+// ガウス整数の剰余演算子「%」
+// 商a / bの実部と虚部をそれぞれ最も近い整数に丸めてqとし、a - qbを余りとする
+// 丸めの誤差は各成分で1/2以下なので、余りのノルムは常に割る数のノルムより小さくなる(ユークリッド性)
+// 整数と同様に、0で割るとパニックする
+// 途中の計算はchecked_divと同じくi128で行う。余りの各成分の絶対値は(|c| + |d|) / 2以下に収まり、
+// c = d = i32::MINの場合も-2^31以上2^31未満となるので、i32への変換は失敗しない
+impl Rem for Complex<i32> {
+    type Output = Complex<i32>;
+    fn rem(self, rhs: Complex<i32>) -> Complex<i32> {
+        let (a, b) = (i128::from(self.re), i128::from(self.im));
+        let (c, d) = (i128::from(rhs.re), i128::from(rhs.im));
+        let denom = c * c + d * d;
+        assert!(denom != 0, "attempt to calculate the remainder with a divisor of zero");
+        let q_re = round_div(a * c + b * d, denom);
+        let q_im = round_div(b * c - a * d, denom);
+        let re = a - (q_re * c - q_im * d);
+        let im = b - (q_re * d + q_im * c);
+        Complex {
+            re: i32::try_from(re).expect("remainder fits in i32"),
+            im: i32::try_from(im).expect("remainder fits in i32")
+        }
+    }
+}
+
+impl RemAssign for Complex<i32> {
+    fn rem_assign(&mut self, rhs: Complex<i32>) {
+        *self = *self % rhs;
+    }
+}
+
+// ガウス整数の最大公約数をユークリッドの互除法で求める
+// 「%」をオーバーロードしているため、整数の互除法と同じコードがそのまま使える
+// 最大公約数は単位数(±1, ±i)倍の違いを除いて一意に定まり、どれが返るかは入力による
+fn gaussian_gcd(a: Complex<i32>, b: Complex<i32>) -> Complex<i32> {
+    let (mut a, mut b) = (a, b);
+    let zero = Complex { re: 0, im: 0 };
+    while b != zero {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+// 複素数には自然な全順序がないため、Complex自体にはOrdを実装しない
+// 並べ替えの順序を決めたい場合のために、実部、虚部の順に比較する辞書式順序のラッパー型を用意する
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LexOrd<T>(pub Complex<T>);
+
+impl<T: Ord> PartialOrd for LexOrd<T> {
+    fn partial_cmp(&self, other: &LexOrd<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for LexOrd<T> {
+    fn cmp(&self, other: &LexOrd<T>) -> Ordering {
+        self.0.re.cmp(&other.0.re).then_with(|| self.0.im.cmp(&other.0.im))
+    }
+}
+
+// 絶対値の大きさで比較するラッパー型
+// 絶対値だけを比べるため、偏角が異なっても絶対値が等しければEqualになり、==でも等しいとみなす
+// 絶対値はhypotで求めるため、絶対値の2乗がオーバーフロー/アンダーフローするほど大きい/小さい値でも正しく比べられる
+// f64::total_cmpで比較するので、NaNを含む値も含めて全順序になる
+// total_cmpは符号が負のNaNを最も小さくするが、NaNの符号は計算によって異なるため、
+// 絶対値をabsで正の値にそろえ、NaNは常に最も大きい値として扱う
+#[derive(Clone, Copy, Debug)]
+pub struct MagOrd(pub Complex<f64>);
+
+impl PartialEq for MagOrd {
+    fn eq(&self, other: &MagOrd) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for MagOrd {}
+
+impl PartialOrd for MagOrd {
+    fn partial_cmp(&self, other: &MagOrd) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for MagOrd {
+    fn cmp(&self, other: &MagOrd) -> Ordering {
+        let (a, b) = (self.0.re.hypot(self.0.im), other.0.re.hypot(other.0.im));
+        a.abs().total_cmp(&b.abs())
+    }
+}
+
+// Complex<f64>からComplex<i32>への変換に失敗した理由
+#[derive(Debug, PartialEq)]
+pub enum TryFromComplexError {
+    NotFinite,
+    OutOfRange
+}
+
+impl fmt::Display for TryFromComplexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TryFromComplexError::NotFinite => write!(f, "component is NaN or infinite"),
+            TryFromComplexError::OutOfRange => write!(f, "component is out of i32 range")
+        }
+    }
+}
+
+impl std::error::Error for TryFromComplexError {}
+
+fn round_to_i32(x: f64) -> Result<i32, TryFromComplexError> {
+    if !x.is_finite() {
+        return Err(TryFromComplexError::NotFinite);
+    }
+    let rounded = x.round();
+    if rounded < f64::from(i32::MIN) || rounded > f64::from(i32::MAX) {
+        return Err(TryFromComplexError::OutOfRange);
+    }
+    Ok(rounded as i32)
+}
+
+// 計算結果のComplex<f64>を整数の格子点に丸める変換
+// 各成分を最も近い整数に丸め(ちょうど中間の値は0から遠い方)、NaNや無限大、
+// i32に収まらない値を含む場合はエラーを返す
+impl TryFrom<Complex<f64>> for Complex<i32> {
+    type Error = TryFromComplexError;
+    fn try_from(c: Complex<f64>) -> Result<Complex<i32>, TryFromComplexError> {
+        Ok(Complex { re: round_to_i32(c.re)?, im: round_to_i32(c.im)? })
+    }
+}
+
+// Complexと同じく2つの成分を持つ平面ベクトル
+// 加減算や符号反転、スカラー倍はComplexと同じ成分ごとの演算になるが、
+// ベクトル同士の「*」は定義せず、内積はdotメソッドで求める
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Vector2<T> {
+    pub x: T,
+    pub y: T
+}
+
+impl<T: Add<Output=T>> Add for Vector2<T> {
+    type Output = Vector2<T>;
+    fn add(self, rhs: Vector2<T>) -> Vector2<T> {
+        Vector2 { x: self.x + rhs.x, y: self.y + rhs.y }
+    }
+}
+
+impl<T: Sub<Output=T>> Sub for Vector2<T> {
+    type Output = Vector2<T>;
+    fn sub(self, rhs: Vector2<T>) -> Vector2<T> {
+        Vector2 { x: self.x - rhs.x, y: self.y - rhs.y }
+    }
+}
+
+impl<T: Neg<Output=T>> Neg for Vector2<T> {
+    type Output = Vector2<T>;
+    fn neg(self) -> Vector2<T> {
+        Vector2 { x: -self.x, y: -self.y }
+    }
+}
+
+// スカラー倍は各成分を独立に定数倍するだけで、成分同士が混ざらない
+// Complex同士の「*」は(ac - bd) + (ad + bc)iのように実部と虚部が交差して掛け合わされ、
+// 平面上では回転と拡大縮小を表す点が異なる
+impl<T: Mul<Output=T> + Copy> Mul<T> for Vector2<T> {
+    type Output = Vector2<T>;
+    fn mul(self, rhs: T) -> Vector2<T> {
+        Vector2 { x: self.x * rhs, y: self.y * rhs }
+    }
+}
+
+impl<T> Vector2<T> {
+    // 内積 x1 * x2 + y1 * y2
+    pub fn dot(self, other: Vector2<T>) -> T
+        where T: Add<Output=T> + Mul<Output=T>
+    {
+        self.x * other.x + self.y * other.y
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Interval<T> {
+    lower: T, //inclusive(含まれる)
+    upper: T // exclusive(含まれない)
+}
+
+// 区間全体のPartialEqには頼らず、境界値同士のPartialOrdだけで比較する
+// 境界が浮動小数点数でNaNを含む場合は、どの条件も満たさずNoneになる
+impl<T: PartialOrd> PartialOrd<Interval<T>> for Interval<T> {
+    fn partial_cmp(&self, other: &Interval<T>) -> Option<Ordering> {
+        let same_lower = self.lower.partial_cmp(&other.lower) == Some(Ordering::Equal);
+        let same_upper = self.upper.partial_cmp(&other.upper) == Some(Ordering::Equal);
+        if same_lower && same_upper { Some(Ordering::Equal) }
+        else if self.lower >= other.upper { Some(Ordering::Greater) }
+        else if self.upper <= other.lower { Some(Ordering::Less) }
+        else { None }
+    }
+}
+
+// 区間演算の「+」 [a, b) + [c, d) = [a + c, b + d)
+impl<T: Add<Output=T>> Add for Interval<T> {
+    type Output = Interval<T>;
+    fn add(self, rhs: Interval<T>) -> Interval<T> {
+        Interval { lower: self.lower + rhs.lower, upper: self.upper + rhs.upper }
+    }
+}
+
+// スカラー値の「+」「-」は区間の両端を同じだけ平行移動する
+// 区間同士のAddとは右辺の型が異なるため、別のimplとして共存できる
+impl<T: Add<Output=T> + Copy> Add<T> for Interval<T> {
+    type Output = Interval<T>;
+    fn add(self, rhs: T) -> Interval<T> {
+        Interval { lower: self.lower + rhs, upper: self.upper + rhs }
+    }
+}
+
+impl<T: Sub<Output=T> + Copy> Sub<T> for Interval<T> {
+    type Output = Interval<T>;
+    fn sub(self, rhs: T) -> Interval<T> {
+        Interval { lower: self.lower - rhs, upper: self.upper - rhs }
+    }
+}
+
+impl<T> Interval<T> {
+    // 下限は含み、上限は含まない
+    fn contains(&self, value: &T) -> bool
+        where T: PartialOrd
+    {
+        *value >= self.lower && *value < self.upper
+    }
+
+    // 2つの区間の共通部分。上限は含まないため、端点が接するだけの区間同士はNoneになる
+    fn intersection(self, other: Interval<T>) -> Option<Interval<T>>
+        where T: Ord + Copy
+    {
+        let lower = std::cmp::max(self.lower, other.lower);
+        let upper = std::cmp::min(self.upper, other.upper);
+        if lower < upper { Some(Interval { lower, upper }) } else { None }
+    }
+
+    // 区間の幅 upper - lower
+    fn width(&self) -> T
+        where T: Sub<Output=T> + Copy
+    {
+        self.upper - self.lower
+    }
+
+    // 上限は含まないため、lower == upperの区間も空になる
+    fn is_empty(&self) -> bool
+        where T: PartialOrd
+    {
+        self.lower >= self.upper
+    }
+
+    // 値を区間の範囲に収める
+    // 上限より小さい最大の値は型によって異なり汎用には求められないため、
+    // 上限を超える値はupperに丸める(upper自身は区間に含まれないことに注意)
+    fn clamp(&self, value: T) -> T
+        where T: Ord + Copy
+    {
+        std::cmp::min(std::cmp::max(value, self.lower), self.upper)
+    }
+
+    // 2つの区間が1点以上を共有するかどうか
+    // 上限は含まないため、端点が接するだけの区間同士は重ならない
+    fn overlaps(&self, other: &Interval<T>) -> bool
+        where T: PartialOrd
+    {
+        self.lower < other.upper && other.lower < self.upper
+    }
+
+    // othersのどの区間とも重ならないかどうか
+    fn disjoint_from_all(&self, others: &[Interval<T>]) -> bool
+        where T: PartialOrd
+    {
+        others.iter().all(|other| !self.overlaps(other))
+    }
+
+    // pointで[lower, point)と[point, upper)に分割する
+    // pointが区間の外にある場合は区間全体が片側に入り、空になる側はNoneになる
+    fn split_at(self, point: T) -> (Option<Interval<T>>, Option<Interval<T>>)
+        where T: PartialOrd + Copy
+    {
+        let left = if self.lower < point {
+            Some(Interval { lower: self.lower, upper: if point < self.upper { point } else { self.upper } })
+        } else {
+            None
+        };
+        let right = if point < self.upper {
+            Some(Interval { lower: if self.lower < point { point } else { self.lower }, upper: self.upper })
+        } else {
+            None
+        };
+        (left, right)
+    }
+
+    // 両方の区間を含む最小の区間(凸包)
+    fn hull(self, other: Interval<T>) -> Interval<T>
+        where T: Ord + Copy
+    {
+        Interval {
+            lower: std::cmp::min(self.lower, other.lower),
+            upper: std::cmp::max(self.upper, other.upper)
+        }
+    }
+
+    // 両端をmarginだけ外側に広げる [lower - margin, upper + margin)
+    fn expand(self, margin: T) -> Interval<T>
+        where T: Add<Output=T> + Sub<Output=T> + Copy
+    {
+        Interval { lower: self.lower - margin, upper: self.upper + margin }
+    }
+
+    // 両端をmarginだけ内側に縮める [lower + margin, upper - margin)
+    // 縮めた結果、下限と上限が逆転する場合は幅0の空の区間[lower + margin, lower + margin)にする
+    fn contract(self, margin: T) -> Interval<T>
+        where T: Add<Output=T> + Sub<Output=T> + PartialOrd + Copy
+    {
+        let lower = self.lower + margin;
+        let upper = self.upper - margin;
+        if lower < upper { Interval { lower, upper } } else { Interval { lower, upper: lower } }
+    }
+}
+
+// 論理演算子「&」のオーバーロードで区間の共通部分を求める
+// 空の区間を表す値を決めずに済むよう、出力はintersectionと同じOption<Interval<T>>とする
+impl<T: Ord + Copy> BitAnd for Interval<T> {
+    type Output = Option<Interval<T>>;
+    fn bitand(self, rhs: Interval<T>) -> Option<Interval<T>> {
+        self.intersection(rhs)
+    }
+}
+
+// 論理演算子「|」のオーバーロードで両方の区間を含む最小の区間(凸包)を求める
+// 和集合とは異なり、離れた区間同士では間の隙間も結果に含まれる
+impl<T: Ord + Copy> BitOr for Interval<T> {
+    type Output = Interval<T>;
+    fn bitor(self, rhs: Interval<T>) -> Interval<T> {
+        self.hull(rhs)
+    }
+}
+
+// 半開区間の記法で「[10, 20)」のように表示する
+// 境界値の表示は型のDisplayに委譲するため、{:.1}などの指定は境界値それぞれに反映される
+impl<T: fmt::Display> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("[")?;
+        self.lower.fmt(f)?;
+        f.write_str(", ")?;
+        self.upper.fmt(f)?;
+        f.write_str(")")
+    }
+}
+
+// for x in intervalのように整数の区間を反復できるIntoIteratorの実装
+// 範囲lower..upperがイテレータになる型(i32などの整数型)を境界に持つ区間が対象
+impl<T> IntoIterator for Interval<T>
+    where std::ops::Range<T>: Iterator<Item=T>
+{
+    type Item = T;
+    type IntoIter = std::ops::Range<T>;
+    fn into_iter(self) -> std::ops::Range<T> {
+        self.lower..self.upper
+    }
+}
+
+// 算術演算子「%」のオーバーロードで区間の差集合を求める
+// a % bはaのうちbに含まれない部分で、bがaの内側にあると左右2つに分かれるためVecで返す
+// - bがaと重ならない場合はaそのもの
+// - bがaの片端と重なる場合は残った1つの区間
+// - bがaを覆う場合やaが空の場合は空のVec
+impl<T: Ord + Copy> Rem for Interval<T> {
+    type Output = Vec<Interval<T>>;
+    fn rem(self, rhs: Interval<T>) -> Vec<Interval<T>> {
+        if self.is_empty() {
+            return vec![];
+        }
+        if rhs.is_empty() || !self.overlaps(&rhs) {
+            return vec![self];
+        }
+        let mut rest = Vec::new();
+        if self.lower < rhs.lower {
+            rest.push(Interval { lower: self.lower, upper: rhs.lower });
+        }
+        if rhs.upper < self.upper {
+            rest.push(Interval { lower: rhs.upper, upper: self.upper });
+        }
+        rest
+    }
+}
+
+impl Interval<f64> {
+    // 区間の中点
+    // 整数の区間では(lower + upper) / 2の切り捨て方が曖昧になるため、浮動小数点数に限定する
+    fn midpoint(&self) -> f64 {
+        (self.lower + self.upper) / 2.0
     }
-}
 
-#[derive(Debug, PartialEq)]
-struct Interval<T> {
-    lower: T, //inclusive(含まれる)
-    upper: T // exclusive(含まれない)
+    // lowerから幅のn分の1ずつ進めたn個の点。上限は含まない
+    fn sample(&self, n: usize) -> Vec<f64> {
+        let step = self.width() / n as f64;
+        (0..n).map(|i| self.lower + step * i as f64).collect()
+    }
 }
 
-impl<T: PartialOrd> PartialOrd<Interval<T>> for Interval<T> {
-    fn partial_cmp(&self, other: &Interval<T>) -> Option<Ordering> {
-        if self == other { Some(Ordering::Equal) }
-        else if self.lower >= other.upper { Some(Ordering::Greater) }
-        else if self.upper <= other.lower { Some(Ordering::Less) }
-        else { None }
+// 区間のリストを、重なる区間や隣接する区間をまとめた最小の互いに素な区間の集合に置き換える
+// 下限で並べ替えてから、直前の区間の上限に届く区間を順に併合していく
+// 空の区間は点を含まないため取り除く
+fn merge_all<T: Ord + Copy>(intervals: &mut Vec<Interval<T>>) {
+    intervals.retain(|i| !i.is_empty());
+    intervals.sort_by_key(|i| i.lower);
+    let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+    for next in intervals.drain(..) {
+        match merged.last_mut() {
+            Some(last) if next.lower <= last.upper => last.upper = std::cmp::max(last.upper, next.upper),
+            _ => merged.push(next)
+        }
     }
+    *intervals = merged;
 }
 
 // image[row][column] = ...;
 // 上記のように代入を可能にするIndex/IndexMutの実装コード
 
+#[derive(Clone, Debug)]
 struct Image<P> {
     width: usize,
     pixels: Vec<P>
@@ -93,23 +1083,415 @@ impl<P: Default + Copy> Image<P> {
             pixels: vec![P::default(); width * height]
         }
     }
+
+    // (row, col, pixel)の組で指定したピクセルだけを設定し、残りはP::default()とする
+    // 範囲外の座標はIndexMutと同様にパニックする
+    fn from_sparse<I: IntoIterator<Item=(usize, usize, P)>>(width: usize, height: usize, iter: I) -> Image<P> {
+        let mut image = Image::new(width, height);
+        for (row, col, pixel) in iter {
+            image[(row, col)] = pixel;
+        }
+        image
+    }
+}
+
+impl<P> Image<P> {
+    // 範囲外の座標でパニックせずNoneを返すピクセルの参照
+    fn get(&self, row: usize, col: usize) -> Option<&P> {
+        if col >= self.width || row >= self.height() {
+            return None;
+        }
+        self.pixels.get(row * self.width + col)
+    }
+
+    fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut P> {
+        if col >= self.width || row >= self.height() {
+            return None;
+        }
+        self.pixels.get_mut(row * self.width + col)
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    // 高さは保持していないため、ピクセル数を幅で割って求める
+    // 幅が0の画像は高さも0とする
+    fn height(&self) -> usize {
+        self.pixels.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    // 各ピクセルをfで変換した、同じ大きさの新しい画像を返す
+    fn map<Q, F: FnMut(&P) -> Q>(&self, f: F) -> Image<Q> {
+        Image { width: self.width, pixels: self.pixels.iter().map(f).collect() }
+    }
+
+    // 各ピクセルをf(row, col)の戻り値で埋めた画像を作る
+    // newと違い、PにDefaultやCopyを要求しない
+    fn from_fn<F: FnMut(usize, usize) -> P>(width: usize, height: usize, mut f: F) -> Image<P> {
+        let mut pixels = Vec::with_capacity(width * height);
+        for row in 0..height {
+            for col in 0..width {
+                pixels.push(f(row, col));
+            }
+        }
+        Image { width, pixels }
+    }
+
+    // 各行をスライスとして順に返すイテレータ
+    // 幅が0の画像はピクセルを持たないため、chunksに1を渡しても何も返さない
+    fn rows(&self) -> impl Iterator<Item=&[P]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+
+    fn rows_mut(&mut self) -> impl Iterator<Item=&mut [P]> {
+        self.pixels.chunks_mut(self.width.max(1))
+    }
+
+    // 行と列を入れ替えた画像。新しい画像の(r, c)は元の画像の(c, r)になる
+    fn transpose(&self) -> Image<P>
+        where P: Copy
+    {
+        Image::from_fn(self.height(), self.width, |r, c| self[(c, r)])
+    }
+
+    // 左右反転。各行のピクセルを逆順に並べる
+    fn flip_horizontal(&self) -> Image<P>
+        where P: Copy
+    {
+        let width = self.width;
+        Image::from_fn(width, self.height(), |r, c| self[(r, width - 1 - c)])
+    }
+
+    // 上下反転。行の順序を逆にする
+    fn flip_vertical(&self) -> Image<P>
+        where P: Copy
+    {
+        let height = self.height();
+        Image::from_fn(self.width, height, |r, c| self[(height - 1 - r, c)])
+    }
+
+    // (row, col)を左上とするwidth x heightの領域を切り出す
+    // 領域が画像からはみ出す場合は、Index同様にパニックする
+    fn crop(&self, row: usize, col: usize, width: usize, height: usize) -> Image<P>
+        where P: Copy
+    {
+        assert!(row + height <= self.height() && col + width <= self.width,
+                "crop region {}x{} at ({}, {}) exceeds image {}x{}",
+                width, height, row, col, self.width, self.height());
+        Image::from_fn(width, height, |r, c| self[(row + r, col + c)])
+    }
+
+    // すべてのピクセルをvalueで上書きする
+    fn fill(&mut self, value: P)
+        where P: Copy
+    {
+        for p in self.pixels.iter_mut() {
+            *p = value;
+        }
+    }
+
+    // 指定した列のピクセルを上から順に返すイテレータ
+    // 列は幅おきに並んでいるため、width刻みで走査する
+    // 範囲外の列はIndex同様にパニックする。高さ0の画像では空のイテレータになる
+    fn column(&self, col: usize) -> impl Iterator<Item=&P> {
+        assert!(col < self.width, "column {} out of range for width {}", col, self.width);
+        self.pixels.iter().skip(col).step_by(self.width)
+    }
+
+    // 外部で作られた行優先のピクセル列から画像を作る
+    // ピクセル数が幅で割り切れない場合は、行の途中で終わってしまうためパニックする
+    // 幅が0の場合、is_multiple_ofはピクセル数も0のときだけtrueを返す
+    fn from_raw(width: usize, pixels: Vec<P>) -> Image<P> {
+        assert!(pixels.len().is_multiple_of(width),
+                "pixel count {} is not a multiple of width {}", pixels.len(), width);
+        Image { width, pixels }
+    }
+
+    // 画像を消費して内部のピクセル列を取り出す
+    fn into_raw(self) -> Vec<P> {
+        self.pixels
+    }
+
+    // 時計回りに90度回転。転置してから左右反転する
+    fn rotate90_cw(&self) -> Image<P>
+        where P: Copy
+    {
+        self.transpose().flip_horizontal()
+    }
+
+    // 反時計回りに90度回転。転置してから上下反転する
+    fn rotate90_ccw(&self) -> Image<P>
+        where P: Copy
+    {
+        self.transpose().flip_vertical()
+    }
+
+    // srcの左上が(row, col)に来るように重ねて書き込む
+    // 画像の外にはみ出した部分は書き込まずに切り捨てる
+    fn blit(&mut self, src: &Image<P>, row: usize, col: usize)
+        where P: Copy
+    {
+        let rows = src.height().min(self.height().saturating_sub(row));
+        let cols = src.width.min(self.width.saturating_sub(col));
+        for r in 0..rows {
+            for c in 0..cols {
+                self[(row + r, col + c)] = src[(r, c)];
+            }
+        }
+    }
+
+    // 行の区切りを気にせず、全ピクセルを行優先の順に返すイテレータ
+    fn pixels(&self) -> impl Iterator<Item=&P> {
+        self.pixels.iter()
+    }
+
+    fn pixels_mut(&mut self) -> impl Iterator<Item=&mut P> {
+        self.pixels.iter_mut()
+    }
+
+    // (row, col, &pixel)の組を行優先の順に返すイテレータ
+    // 座標は1次元の添字を幅で割った商と余りから求める
+    fn enumerate_pixels(&self) -> impl Iterator<Item=(usize, usize, &P)> {
+        let width = self.width;
+        self.pixels.iter().enumerate().map(move |(i, p)| (i / width, i % width, p))
+    }
+
+    // 最近傍法による拡大縮小
+    // 新しい画像の各ピクセルに、元の画像で対応する位置のピクセルをそのまま使う
+    // 空の画像には参照できるピクセルがないため、空でない大きさへの拡大はパニックする
+    fn resize_nearest(&self, new_width: usize, new_height: usize) -> Image<P>
+        where P: Copy
+    {
+        assert!(new_width == 0 || new_height == 0 || !self.pixels.is_empty(),
+                "cannot resize an empty image to {}x{}", new_width, new_height);
+        let (width, height) = (self.width, self.height());
+        Image::from_fn(new_width, new_height, |r, c| {
+            self[(r * height / new_height, c * width / new_width)]
+        })
+    }
+
+    // 各ピクセルを可変参照でfに渡し、その場で書き換える
+    // mapと違い新しい画像を確保しないため、変換後の型が同じ場合に使う
+    fn apply_in_place<F: FnMut(&mut P)>(&mut self, f: F) {
+        self.pixels.iter_mut().for_each(f);
+    }
+
+    // 上下左右に指定した幅の枠を付けた画像を作る。枠のピクセルはfillで埋める
+    fn pad(&self, top: usize, bottom: usize, left: usize, right: usize, fill: P) -> Image<P>
+        where P: Copy
+    {
+        let mut padded = Image::from_fn(left + self.width + right, top + self.height() + bottom, |_, _| fill);
+        padded.blit(self, top, left);
+        padded
+    }
+
+    // predを満たすピクセルの数
+    fn count<F: Fn(&P) -> bool>(&self, pred: F) -> usize {
+        self.pixels.iter().filter(|p| pred(p)).count()
+    }
 }
 
 impl<P> std::ops::Index<usize> for Image<P> {
     type Output = [P];
     fn index(&self, row: usize) -> &[P] {
         let start = row * self.width;
-        return &self.pixels[start .. start +  self.width];
+        &self.pixels[start .. start +  self.width]
     }
 }
 
 impl<P> std::ops::IndexMut<usize> for Image<P> {
     fn index_mut(&mut self, row: usize) -> &mut [P] {
         let start = row * self.width;
-        return &mut self.pixels[start .. start + self.width];
+        &mut self.pixels[start .. start + self.width]
+    }
+}
+
+// image[1..3]のように範囲を指定すると、複数行分のピクセルを1つのスライスで返す
+impl<P> std::ops::Index<std::ops::Range<usize>> for Image<P> {
+    type Output = [P];
+    fn index(&self, rows: std::ops::Range<usize>) -> &[P] {
+        &self.pixels[rows.start * self.width .. rows.end * self.width]
+    }
+}
+
+impl<P> std::ops::IndexMut<std::ops::Range<usize>> for Image<P> {
+    fn index_mut(&mut self, rows: std::ops::Range<usize>) -> &mut [P] {
+        &mut self.pixels[rows.start * self.width .. rows.end * self.width]
+    }
+}
+
+// image[(row, column)] = ...;
+// 行のスライスを経由せず、タプルの添字で1ピクセルを直接読み書きする
+// image[row][column]と同じく、幅を超える列は次の行に回り込まずパニックする
+impl<P> std::ops::Index<(usize, usize)> for Image<P> {
+    type Output = P;
+    fn index(&self, (row, col): (usize, usize)) -> &P {
+        assert!(col < self.width, "column {} out of range for width {}", col, self.width);
+        &self.pixels[row * self.width + col]
+    }
+}
+
+impl<P> std::ops::IndexMut<(usize, usize)> for Image<P> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut P {
+        assert!(col < self.width, "column {} out of range for width {}", col, self.width);
+        &mut self.pixels[row * self.width + col]
+    }
+}
+
+// ピクセルごとの「+」で画像を合成する
+// Addの出力型をResultにすると「a + b + c」と連結できなくなるため、
+// 画像の大きさが異なる場合はパニックする
+impl<P: Add<Output=P> + Copy> Add for Image<P> {
+    type Output = Image<P>;
+    fn add(self, rhs: Image<P>) -> Image<P> {
+        assert!(self.width == rhs.width && self.pixels.len() == rhs.pixels.len(),
+                "image dimensions mismatch: {}x{} + {}x{}",
+                self.width, self.height(), rhs.width, rhs.height());
+        Image {
+            width: self.width,
+            pixels: self.pixels.iter().zip(rhs.pixels.iter()).map(|(&a, &b)| a + b).collect()
+        }
+    }
+}
+
+// ピクセルごとの「-」で画像の差分を求める
+// Addと同じく、画像の大きさが異なる場合はパニックする
+impl<P: Sub<Output=P> + Copy> Sub for Image<P> {
+    type Output = Image<P>;
+    fn sub(self, rhs: Image<P>) -> Image<P> {
+        assert!(self.width == rhs.width && self.pixels.len() == rhs.pixels.len(),
+                "image dimensions mismatch: {}x{} - {}x{}",
+                self.width, self.height(), rhs.width, rhs.height());
+        Image {
+            width: self.width,
+            pixels: self.pixels.iter().zip(rhs.pixels.iter()).map(|(&a, &b)| a - b).collect()
+        }
+    }
+}
+
+// 幅とピクセル列が等しければ同じ画像とみなす
+// ピクセル数が同じでも幅が異なれば形が違うため等しくない
+impl<P: PartialEq> PartialEq for Image<P> {
+    fn eq(&self, other: &Image<P>) -> bool {
+        self.width == other.width && self.pixels == other.pixels
+    }
+}
+
+impl Image<u8> {
+    // グレースケール画像をバイナリ形式のPGM(P5)として書き出す
+    fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P5\n{} {}\n255\n", self.width, self.height()).into_bytes();
+        out.extend_from_slice(&self.pixels);
+        out
+    }
+
+    // 輝度ごとのピクセル数
+    fn histogram(&self) -> [u32; 256] {
+        let mut bins = [0u32; 256];
+        for &p in &self.pixels {
+            bins[usize::from(p)] += 1;
+        }
+        bins
+    }
+}
+
+impl Image<(u8, u8, u8)> {
+    // RGB画像をバイナリ形式のPPM(P6)として書き出す
+    fn to_ppm(&self) -> Vec<u8> {
+        let mut out = format!("P6\n{} {}\n255\n", self.width, self.height()).into_bytes();
+        for &(r, g, b) in &self.pixels {
+            out.extend_from_slice(&[r, g, b]);
+        }
+        out
+    }
+
+    // R、G、Bの各チャンネルをグレースケール画像として取り出す
+    fn split_channels(&self) -> (Image<u8>, Image<u8>, Image<u8>) {
+        (self.map(|&(r, _, _)| r), self.map(|&(_, g, _)| g), self.map(|&(_, _, b)| b))
+    }
+}
+
+// R、G、Bのグレースケール画像を1枚のRGB画像にまとめる
+// 3枚の画像の大きさが異なる場合はパニックする
+fn merge_channels(r: &Image<u8>, g: &Image<u8>, b: &Image<u8>) -> Image<(u8, u8, u8)> {
+    assert!(r.width == g.width && r.width == b.width
+            && r.pixels.len() == g.pixels.len() && r.pixels.len() == b.pixels.len(),
+            "channel dimensions mismatch");
+    Image {
+        width: r.width,
+        pixels: r.pixels.iter().zip(&g.pixels).zip(&b.pixels).map(|((&r, &g), &b)| (r, g, b)).collect()
+    }
+}
+
+impl Image<f64> {
+    // ksize x ksizeのカーネルによる2次元の畳み込み
+    // 畳み込みの定義通りカーネルは反転して適用する(対称なカーネルでは違いはない)
+    // 画像の外側を参照する場合は、最も近い端のピクセルの値を使う(クランプ)
+    fn convolve(&self, kernel: &[f64], ksize: usize) -> Image<f64> {
+        assert!(ksize % 2 == 1 && kernel.len() == ksize * ksize,
+                "kernel must be {}x{} with an odd size", ksize, ksize);
+        let (width, height) = (self.width as isize, self.height() as isize);
+        let half = (ksize / 2) as isize;
+        Image::from_fn(self.width, self.height(), |r, c| {
+            let mut acc = 0.0;
+            for i in 0..ksize {
+                for j in 0..ksize {
+                    let sr = (r as isize + half - i as isize).max(0).min(height - 1);
+                    let sc = (c as isize + half - j as isize).max(0).min(width - 1);
+                    acc += kernel[i * ksize + j] * self[(sr as usize, sc as usize)];
+                }
+            }
+            acc
+        })
+    }
+
+    // 各ピクセルを虚部0の複素数とみなした画像に変換する
+    fn to_complex(&self) -> Image<Complex<f64>> {
+        self.map(|&v| Complex::from(v))
     }
 }
 
+impl Image<Complex<f64>> {
+    // 各ピクセルの実部だけを取り出す
+    fn real_part(&self) -> Image<f64> {
+        self.map(|c| c.re)
+    }
+}
+
+// スカラー値の「*」で全ピクセルの輝度を定数倍する
+// 整数型のピクセルでは通常の整数演算と同じく、オーバーフローはデバッグビルドでパニックし、
+// リリースビルドでは折り返す。image * 2u8のような値の飽和が必要なら、mapでsaturating_mulを使う
+impl<P: Mul<Output=P> + Copy> Mul<P> for Image<P> {
+    type Output = Image<P>;
+    fn mul(self, rhs: P) -> Image<P> {
+        Image { width: self.width, pixels: self.pixels.into_iter().map(|p| p * rhs).collect() }
+    }
+}
+
+// 単項演算子「-」で符号付きのピクセルを反転する
+// u8などの符号なし整数はNegを実装しないため対象外で、明るさを反転するなら
+// image.map(|&p| 255 - p)のように明示的に書く
+impl<P: Neg<Output=P> + Copy> Neg for Image<P> {
+    type Output = Image<P>;
+    fn neg(self) -> Image<P> {
+        Image { width: self.width, pixels: self.pixels.into_iter().map(|p| -p).collect() }
+    }
+}
+
+// クロージャの実行がパニックするかどうかを返す
+// 期待通りのパニックでメッセージが出力されないよう、実行中はパニックフックを差し替える
+fn panics<F: FnOnce() -> R + std::panic::UnwindSafe, R>(f: F) -> bool {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let result = std::panic::catch_unwind(f);
+    std::panic::set_hook(hook);
+    result.is_err()
+}
+
+// NaNの比較など、学習用にあえて書いている式をclippyが指摘しないようにする
+#[allow(clippy::eq_op, clippy::zero_divided_by_zero, clippy::bool_assert_comparison,
+        clippy::nonminimal_bool, clippy::neg_cmp_op_on_partial_ord)]
 fn main() {
     // std::ops::Addトレイトをスコープ内でuseすると
     // a + bをa.add(b)と関数呼び出しでも書ける
@@ -122,6 +1504,420 @@ fn main() {
     let y = Complex { re: 2, im: 5 };
     assert_ne!(x, y);
 
+    // 算術演算子「-」と複合代入演算子「-=」
+    let a = Complex { re: 7, im: 3 };
+    let b = Complex { re: 2, im: 5 };
+    assert_eq!(a - b, Complex { re: 5, im: -2 });
+    assert_eq!(Complex { re: 1.5f64, im: 0.25 } - Complex { re: 0.5, im: 1.0 },
+               Complex { re: 1.0, im: -0.75 });
+    // Complex<i32> - Complex<i32>は、Sub<R, Output=O>のL, R, Oがすべてi32となる
+    let d: Complex<i32> = Complex { re: 10i32, im: 20i32 } - Complex { re: 3i32, im: 4i32 };
+    assert_eq!(d, Complex { re: 7, im: 16 });
+    let mut c = Complex { re: 10, im: 10 };
+    c -= Complex { re: 3, im: 4 };
+    assert_eq!(c, Complex { re: 7, im: 6 });
+    let mut f = Complex { re: 1.0f64, im: 2.0 };
+    f -= Complex { re: 0.5, im: 0.5 };
+    assert_eq!(f, Complex { re: 0.5, im: 1.5 });
+
+    // 算術演算子「*」
+    assert_eq!(Complex { re: 1, im: 2 } * Complex { re: 3, im: 4 }, Complex { re: -5, im: 10 });
+    assert_eq!(Complex { re: 0.5f64, im: 1.5 } * Complex { re: 2.0, im: -1.0 },
+               Complex { re: 2.5, im: 2.5 });
+    // 1 + 0iとの積は元の値と変わらない
+    let z = Complex { re: 7, im: -3 };
+    assert_eq!(z * Complex { re: 1, im: 0 }, z);
+
+    // 共役複素数との積は虚部が0になる
+    assert_eq!(Complex { re: 3, im: 4 }.conjugate(), Complex { re: 3, im: -4 });
+    assert_eq!(Complex { re: 1.5f64, im: -2.0 }.conjugate(), Complex { re: 1.5, im: 2.0 });
+    let c = Complex { re: 3, im: 4 };
+    assert_eq!((c * c.conjugate()).im, 0);
+    assert_eq!(c * c.conjugate(), Complex { re: 25, im: 0 });
+
+    // 絶対値の2乗と絶対値
+    assert_eq!(Complex { re: 3, im: 4 }.norm_sqr(), 25);
+    assert_eq!(Complex { re: 3.0f64, im: 4.0 }.norm(), 5.0);
+    assert_eq!(Complex { re: 3.0f32, im: 4.0 }.norm(), 5.0);
+
+    // 2点間の距離は対称
+    let (o, p) = (Complex::zero(), Complex { re: 3.0, im: 4.0 });
+    assert_eq!(o.dist(p), 5.0);
+    assert_eq!(p.dist(o), o.dist(p));
+
+    // Displayを実装するとformat!やprintln!の{}で表示できる
+    assert_eq!(format!("{}", Complex { re: 3, im: 4 }), "3+4i");
+    assert_eq!(format!("{}", Complex { re: 3, im: -4 }), "3-4i");
+    assert_eq!(format!("{:.1}", Complex { re: 2.04f64, im: -1.5 }), "2.0-1.5i");
+    // 負のゼロの虚部は「-」で表示し、{:+}でも「+」を重ねない
+    assert_eq!(format!("{}", Complex { re: 3.0, im: -0.0 }), "3-0i");
+    assert_eq!(format!("{:+}", Complex { re: 3, im: 4 }), "+3+4i");
+    assert_eq!(format!("{:+}", Complex { re: 3, im: -4 }), "+3-4i");
+    // 幅の指定は実部だけでなく全体に適用される
+    assert_eq!(format!("[{:<8}]", Complex { re: 3, im: -4 }), "[3-4i    ]");
+    assert_eq!(format!("[{:8}]", Complex { re: 3, im: -4 }), "[    3-4i]");
+    assert_eq!(format!("[{:*^9}]", Complex { re: 3, im: 4 }), "[**3+4i***]");
+    assert_eq!(format!("{:010.1}", Complex { re: -1.5, im: 2.5 }), "-01.5+2.5i");
+    assert_eq!(format!("{:08.1}", Complex { re: 1.25, im: -2.5 }), "1.2-2.5i");
+    assert_eq!(format!("{:2}", Complex { re: 3, im: 4 }), "3+4i");
+
+    // From<T>を実装すると、Intoのブランケット実装によりinto()でも変換できる
+    assert_eq!(Complex::from(2i32), Complex { re: 2, im: 0 });
+    let c: Complex<f64> = 5.0.into();
+    assert_eq!(c, Complex { re: 5.0, im: 0.0 });
+    assert_eq!(Complex::from((3, 4)), Complex { re: 3, im: 4 });
+    let c: Complex<f64> = (1.5, -2.5).into();
+    assert_eq!(c, Complex { re: 1.5, im: -2.5 });
+
+    // タプルへ変換して分解できる
+    let (r, i): (i32, i32) = Complex { re: 3, im: 4 }.into();
+    assert_eq!((r, i), (3, 4));
+
+    // EqとHashを実装したComplex<i32>はHashSetの要素にできる
+    let mut set = HashSet::new();
+    set.insert(Complex { re: 1, im: 2 });
+    set.insert(Complex { re: -3, im: 0 });
+    set.insert(Complex { re: 1, im: 2 });
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(&Complex { re: 1, im: 2 }));
+    assert!(set.contains(&Complex { re: -3, im: 0 }));
+    assert!(!set.contains(&Complex { re: 2, im: 1 }));
+
+    // 複素平面上の格子点をキーにしたHashMap
+    let mut lattice: HashMap<Complex<i32>, i32> = HashMap::new();
+    lattice.insert(Complex { re: 0, im: 0 }, 0);
+    lattice.insert(Complex { re: 3, im: 4 }, 25);
+    lattice.insert(Complex { re: -1, im: 1 }, 2);
+    assert_eq!(lattice[&Complex { re: 3, im: 4 }], 25);
+    assert_eq!(lattice.get(&Complex { re: -1, im: 1 }), Some(&2));
+    assert_eq!(lattice.get(&Complex { re: 4, im: 3 }), None);
+    // let mut m: HashMap<Complex<f64>, i32> = HashMap::new(); は
+    // insert時にComplex<f64>: Hashを満たさずコンパイルエラーになる
+
+    // 実数のスカラー倍
+    assert_eq!(Complex { re: 2, im: 3 } * 10, Complex { re: 20, im: 30 });
+    assert_eq!(Complex { re: 1.5f64, im: -0.5 } * 2.0, Complex { re: 3.0, im: -1.0 });
+    // 左辺がスカラー値でも同じ結果になる
+    assert_eq!(2.0 * Complex { re: 1.0, im: 3.0 }, Complex { re: 2.0, im: 6.0 });
+    assert_eq!(2 * Complex { re: 1, im: 3 }, Complex { re: 2, im: 6 });
+
+    // 参照同士の加算は所有権を移動しない
+    // Metersは&Meters + &Metersのみを実装し、Copyではない成分型
+    #[derive(Clone, Debug, PartialEq)]
+    struct Meters(f64);
+    impl Add<&Meters> for &Meters {
+        type Output = Meters;
+        fn add(self, rhs: &Meters) -> Meters {
+            Meters(self.0 + rhs.0)
+        }
+    }
+    let p = Complex { re: Meters(1.0), im: Meters(2.0) };
+    let q = Complex { re: Meters(0.5), im: Meters(0.25) };
+    assert_eq!(&p + &q, Complex { re: Meters(1.5), im: Meters(2.25) });
+    // pとqはまだ使える
+    assert_eq!(p.re, Meters(1.0));
+    assert_eq!(q.im, Meters(0.25));
+
+    // 算術演算子「/」
+    assert_eq!(Complex { re: 1.0f64, im: 0.0 } / Complex { re: 0.0, im: 1.0 },
+               Complex { re: 0.0, im: -1.0 });
+    assert_eq!(Complex { re: 1.0f32, im: 0.0 } / Complex { re: 0.0, im: 1.0 },
+               Complex { re: 0.0, im: -1.0 });
+    // 自分自身で割ると誤差の範囲で1 + 0iになる
+    let w = Complex { re: 0.3f64, im: -1.7 };
+    assert!((w / w).approx_eq(Complex { re: 1.0, im: 0.0 }, 1e-12));
+
+    // 極形式との相互変換
+    assert!(Complex::from_polar(5.0, 0.0).approx_eq(Complex { re: 5.0, im: 0.0 }, 1e-9));
+    let (r, theta) = Complex { re: 3.0, im: 4.0 }.to_polar();
+    assert!((r - 5.0).abs() < 1e-9);
+    assert!(Complex::from_polar(r, theta).approx_eq(Complex { re: 3.0, im: 4.0 }, 1e-9));
+    // 各成分の差がeps以内であれば等しいとみなす
+    assert!(!Complex { re: 1.0, im: 2.0 }.approx_eq(Complex { re: 2.0, im: 2.0 }, 0.1));
+    assert!(Complex { re: 1.0, im: 2.0 }.approx_eq(Complex { re: 1.05, im: 1.95 }, 0.1));
+
+    // Index/IndexMutで成分を読み書きする
+    let mut c = Complex { re: 3, im: 4 };
+    assert_eq!(c[0], 3);
+    assert_eq!(c[1], 4);
+    c[1] = 9;
+    assert_eq!(c, Complex { re: 3, im: 9 });
+    // 範囲外の添字はパニックする
+    assert!(panics(|| c[2]));
+
+    // FromStrを実装したComplex<f64>は文字列からパースできる
+    assert_eq!("3+4i".parse::<Complex<f64>>(), Ok(Complex { re: 3.0, im: 4.0 }));
+    assert_eq!("-2-5i".parse::<Complex<f64>>(), Ok(Complex { re: -2.0, im: -5.0 }));
+    assert_eq!("7".parse::<Complex<f64>>(), Ok(Complex { re: 7.0, im: 0.0 }));
+    assert_eq!("4i".parse::<Complex<f64>>(), Ok(Complex { re: 0.0, im: 4.0 }));
+    assert_eq!(" 1.5 - 0.5i ".parse::<Complex<f64>>(), Ok(Complex { re: 1.5, im: -0.5 }));
+    // 先頭の符号の後ろにも空白を置ける
+    assert_eq!(" - 3".parse::<Complex<f64>>(), Ok(Complex { re: -3.0, im: 0.0 }));
+    assert_eq!("- 4i".parse::<Complex<f64>>(), Ok(Complex { re: 0.0, im: -4.0 }));
+    assert_eq!("- 3 + 4i".parse::<Complex<f64>>(), Ok(Complex { re: -3.0, im: 4.0 }));
+    assert_eq!("- i".parse::<Complex<f64>>(), Ok(Complex { re: 0.0, im: -1.0 }));
+    assert_eq!(Complex::from_str("1e-3+i"), Ok(Complex { re: 0.001, im: 1.0 }));
+    assert_eq!("".parse::<Complex<f64>>(), Err(ParseComplexError::Empty));
+    assert_eq!("3+4".parse::<Complex<f64>>(), Err(ParseComplexError::MissingImaginaryUnit));
+    assert_eq!("x+4i".parse::<Complex<f64>>(), Err(ParseComplexError::InvalidNumber("x".to_string())));
+
+    // イテレータの総和
+    let v = vec![Complex { re: 1, im: 2 }, Complex { re: 3, im: -4 }, Complex { re: 5, im: 6 }];
+    let total: Complex<i32> = v.into_iter().sum();
+    assert_eq!(total, Complex { re: 9, im: 4 });
+    let empty: Complex<i32> = Vec::new().into_iter().sum();
+    assert_eq!(empty, Complex { re: 0, im: 0 });
+
+    // イテレータの総乗
+    let v = vec![Complex { re: 1.0, im: 1.0 }, Complex { re: 1.0, im: -1.0 }];
+    let prod: Complex<f64> = v.into_iter().product();
+    assert_eq!(prod, Complex { re: 2.0, im: 0.0 });
+    let empty: Complex<f64> = Vec::new().into_iter().product();
+    assert_eq!(empty, Complex { re: 1.0, im: 0.0 });
+
+    // #[derive(Default)]は各フィールドをT::default()で初期化する
+    assert_eq!(Complex::<i32>::default(), Complex { re: 0, im: 0 });
+    assert_eq!(Complex::zero(), Complex::<f64>::default());
+    assert_eq!(Complex::one() * Complex { re: 2.5, im: -1.0 }, Complex { re: 2.5, im: -1.0 });
+    // i^2 = -1
+    assert_eq!(Complex::i() * Complex::i(), Complex { re: -1.0, im: 0.0 });
+
+    // 複合代入演算子「*=」「/=」
+    let mut c = Complex { re: 1.0, im: 2.0 };
+    c *= Complex { re: 3.0, im: 4.0 };
+    assert_eq!(c, Complex { re: 1.0, im: 2.0 } * Complex { re: 3.0, im: 4.0 });
+    c /= Complex { re: 3.0, im: 4.0 };
+    assert!(c.approx_eq(Complex { re: 1.0, im: 2.0 }, 1e-12));
+    let mut q = Complex { re: 1.0, im: 0.0 };
+    q /= Complex::i();
+    assert_eq!(q, Complex { re: 1.0, im: 0.0 } / Complex::i());
+
+    // 整数乗
+    assert!(Complex::i().powi(4).approx_eq(Complex::one(), 1e-12));
+    assert!(Complex { re: 1.0, im: 1.0 }.powi(2).approx_eq(Complex { re: 0.0, im: 2.0 }, 1e-12));
+    assert_eq!(Complex { re: 3.0, im: -7.0 }.powi(0), Complex::one());
+    assert!(Complex::i().powi(-1).approx_eq(Complex { re: 0.0, im: -1.0 }, 1e-12));
+    // 絶対値1の複素数は何乗しても絶対値1のまま
+    assert!((Complex { re: 0.6, im: 0.8 }.powi(1001).norm() - 1.0).abs() < 1e-9);
+
+    // オイラーの等式 e^(iπ) = -1
+    assert!(Complex { re: 0.0, im: PI }.exp().approx_eq(Complex { re: -1.0, im: 0.0 }, 1e-9));
+    assert_eq!(Complex { re: 2.0, im: 0.0 }.exp(), Complex { re: 2.0f64.exp(), im: 0.0 });
+
+    // 主値の平方根
+    assert!(Complex { re: -1.0, im: 0.0 }.sqrt().approx_eq(Complex { re: 0.0, im: 1.0 }, 1e-9));
+    assert_eq!(Complex::zero().sqrt(), Complex::zero());
+    for &z in &[Complex { re: 3.0, im: 4.0 }, Complex { re: -2.0, im: -5.0 }, Complex { re: 0.0, im: -9.0 }] {
+        let root = z.sqrt();
+        assert!(root.re >= 0.0);
+        assert!((root * root).approx_eq(z, 1e-9));
+    }
+
+    // エルミート内積
+    let u = [Complex::one(), Complex::i()];
+    let v = [Complex::one(), -Complex::i()];
+    assert_eq!(hermitian_dot(&u, &v), Complex::zero());
+    let w = [Complex { re: 3.0, im: 4.0 }, Complex { re: 1.0, im: -2.0 }];
+    let self_dot = hermitian_dot(&w, &w);
+    assert_eq!(self_dot, Complex { re: w[0].norm_sqr() + w[1].norm_sqr(), im: 0.0 });
+    assert!(panics(|| hermitian_dot(&u, &w[..1])));
+
+    // ガウス整数として割り切れる場合のみSomeになる割り算
+    // 5 = (2 + i)(2 - i)
+    assert_eq!(Complex { re: 5, im: 0 }.checked_div(Complex { re: 2, im: 1 }), Some(Complex { re: 2, im: -1 }));
+    assert_eq!(Complex { re: 3, im: 0 }.checked_div(Complex { re: 2, im: 1 }), None);
+    assert_eq!(Complex { re: 3, im: 4 }.checked_div(Complex { re: 0, im: 0 }), None);
+    // 成分がi32::MINでも途中の計算はオーバーフローしない
+    let min = Complex { re: i32::MIN, im: i32::MIN };
+    assert_eq!(min.checked_div(min), Some(Complex { re: 1, im: 0 }));
+    assert_eq!(Complex { re: 1, im: 0 }.checked_div(min), None);
+
+    // 成分のスカラー倍とスカラー除算
+    assert_eq!(Complex { re: 1.5, im: -2.0 }.scale(2.0), Complex { re: 3.0, im: -4.0 });
+    assert_eq!(Complex { re: 3.0, im: -4.0 }.unscale(2.0), Complex { re: 1.5, im: -2.0 });
+
+    // 辞書式順序のラッパー型で並べ替える
+    let mut points = vec![LexOrd(Complex { re: 2, im: 1 }), LexOrd(Complex { re: -1, im: 5 }),
+                          LexOrd(Complex { re: 2, im: -3 }), LexOrd(Complex { re: 0, im: 0 })];
+    points.sort();
+    let sorted: Vec<Complex<i32>> = points.into_iter().map(|LexOrd(c)| c).collect();
+    assert_eq!(sorted, vec![Complex { re: -1, im: 5 }, Complex { re: 0, im: 0 },
+                            Complex { re: 2, im: -3 }, Complex { re: 2, im: 1 }]);
+
+    // 線形補間
+    let (from, to) = (Complex { re: 0.0, im: 2.0 }, Complex { re: 4.0, im: -2.0 });
+    assert_eq!(from.lerp(to, 0.0), from);
+    assert_eq!(from.lerp(to, 1.0), to);
+    assert_eq!(from.lerp(to, 0.5), Complex { re: 2.0, im: 0.0 });
+    assert_eq!(from.lerp(to, 2.0), Complex { re: 8.0, im: -6.0 });
+
+    // 実数の「+=」は虚部を変えない
+    let mut c = Complex { re: 1, im: 2 };
+    c += 10;
+    assert_eq!(c, Complex { re: 11, im: 2 });
+    c += Complex { re: 1, im: 1 };
+    assert_eq!(c, Complex { re: 12, im: 3 });
+
+    // Complex<f64>を整数の格子点に丸める
+    assert_eq!(Complex::<i32>::try_from(Complex { re: 2.6, im: -1.2 }), Ok(Complex { re: 3, im: -1 }));
+    assert_eq!(Complex::<i32>::try_from(Complex { re: 1.0, im: f64::NAN }),
+               Err(TryFromComplexError::NotFinite));
+    assert_eq!(Complex::<i32>::try_from(Complex { re: 1.0e10, im: 0.0 }),
+               Err(TryFromComplexError::OutOfRange));
+
+    // 成分の型の変換
+    let wide: Complex<f64> = Complex { re: 3i32, im: -4 }.cast();
+    assert_eq!(wide, Complex { re: 3.0, im: -4.0 });
+    assert_eq!(Complex { re: 7u8, im: 200 }.cast::<i64>(), Complex { re: 7i64, im: 200 });
+    // ジェネリックなコードでもFromの制約だけで使える
+    fn norm_of<T>(c: Complex<T>) -> f64 where f64: From<T> {
+        c.cast::<f64>().norm()
+    }
+    assert_eq!(norm_of(Complex { re: 3i32, im: 4 }), 5.0);
+    assert_eq!(norm_of(Complex { re: 6.0f32, im: 8.0 }), 10.0);
+
+    // 複素数乗 i^2 = -1
+    assert!(Complex::i().powc(Complex { re: 2.0, im: 0.0 }).approx_eq(Complex { re: -1.0, im: 0.0 }, 1e-9));
+    let z = Complex { re: -1.5, im: 0.75 };
+    assert!(z.powc(Complex::one()).approx_eq(z, 1e-9));
+    // i^iは実数 e^(-π/2) になる
+    assert!(Complex::i().powc(Complex::i()).approx_eq(Complex { re: (-PI / 2.0).exp(), im: 0.0 }, 1e-9));
+
+    // 主値の自然対数
+    assert!(Complex::one().ln().approx_eq(Complex::zero(), 1e-12));
+    assert!(Complex { re: -1.0, im: 0.0 }.ln().approx_eq(Complex { re: 0.0, im: PI }, 1e-12));
+    assert_eq!(Complex::zero().ln().re, f64::NEG_INFINITY);
+    for &z in &[Complex { re: 3.0, im: 4.0 }, Complex { re: -0.5, im: -2.0 }, Complex { re: 0.0, im: 7.0 }] {
+        assert!(z.ln().exp().approx_eq(z, 1e-9));
+    }
+
+    // 偏角
+    assert!((Complex { re: 0.0, im: 1.0 }.arg() - PI / 2.0).abs() < 1e-12);
+    assert!((Complex { re: -1.0, im: 0.0 }.arg() - PI).abs() < 1e-12);
+    assert!((Complex { re: 1.0, im: -1.0 }.arg() + PI / 4.0).abs() < 1e-12);
+
+    // 平面ベクトルの演算
+    let p = Vector2 { x: 1, y: 2 };
+    let q = Vector2 { x: 3, y: -4 };
+    assert_eq!(p + q, Vector2 { x: 4, y: -2 });
+    assert_eq!(p - q, Vector2 { x: -2, y: 6 });
+    assert_eq!(-p, Vector2 { x: -1, y: -2 });
+    assert_eq!(p * 3, Vector2 { x: 3, y: 6 });
+    assert_eq!(p.dot(q), -5);
+    // 同じ成分でも、Complexの積は成分が交差するため結果が異なる
+    assert_eq!(Complex { re: 1, im: 2 } * Complex { re: 3, im: -4 }, Complex { re: 11, im: 2 });
+    // 直交するベクトルの内積は0
+    assert_eq!(Vector2 { x: 2.0, y: 0.0 }.dot(Vector2 { x: 0.0, y: 5.0 }), 0.0);
+
+    // 逆数
+    assert!(Complex::i().recip().approx_eq(Complex { re: 0.0, im: -1.0 }, 1e-12));
+    for &z in &[Complex { re: 3.0, im: 4.0 }, Complex { re: -0.25, im: 8.0 }, Complex { re: 2.0, im: 0.0 }] {
+        assert!((z * z.recip()).approx_eq(Complex::one(), 1e-12));
+    }
+
+    // ガウス整数の剰余は割る数よりノルムが小さくなる
+    let divisor = Complex { re: 2, im: 1 };
+    assert_eq!(Complex { re: 5, im: 0 } % divisor, Complex::default());
+    // 成分が大きな値でも途中の計算はオーバーフローしない
+    // ノルムはi32に収まらないため、i64に変換してから比べる
+    let large = Complex { re: 1_500_000_000, im: 1_500_000_000 };
+    for &a in &[Complex { re: 7, im: 3 }, Complex { re: -11, im: 4 }, Complex { re: 100, im: -37 }, Complex { re: 1, im: 0 }, large] {
+        for &b in &[divisor, Complex { re: 3, im: -4 }, Complex { re: 0, im: 5 }, Complex { re: -6, im: 0 },
+                    Complex { re: 1_500_000_000, im: 1_400_000_000 }] {
+            let r = a % b;
+            assert!(r.cast::<i64>().norm_sqr() < b.cast::<i64>().norm_sqr());
+            // 余りを引いた値はbで割り切れる
+            assert!((a - r).checked_div(b).is_some());
+        }
+    }
+    let mut g = Complex { re: 7, im: 3 };
+    g %= divisor;
+    assert_eq!(g, Complex { re: 7, im: 3 } % divisor);
+    assert!(panics(|| Complex { re: 1, im: 1 } % Complex { re: 0, im: 0 }));
+    let max = Complex { re: i32::MAX, im: i32::MAX };
+    assert_eq!(max % max, Complex::default());
+    let min = Complex { re: i32::MIN, im: i32::MIN };
+    assert_eq!(min % min, Complex::default());
+    assert_eq!(max % min, Complex { re: -1, im: -1 });
+
+    // ガウス整数の最大公約数
+    // 5と3は互いに素なので、最大公約数は単位数(ノルム1)になる
+    assert_eq!(gaussian_gcd(Complex { re: 5, im: 0 }, Complex { re: 3, im: 0 }).norm_sqr(), 1);
+    // 5 = (2 + i)(2 - i)なので、5と2 + iの最大公約数は2 + iの単位数倍
+    let g = gaussian_gcd(Complex { re: 5, im: 0 }, Complex { re: 2, im: 1 });
+    assert_eq!(g.norm_sqr(), 5);
+    assert!(Complex { re: 5, im: 0 }.checked_div(g).is_some());
+    assert!(Complex { re: 2, im: 1 }.checked_div(g).is_some());
+    // 成分が大きな値でもオーバーフローせずに求まる
+    let (p, q) = (Complex { re: 2_000_000_000, im: 0 }, Complex { re: 1_999_999_999, im: 7 });
+    let g = gaussian_gcd(p, q);
+    assert_eq!(g.norm_sqr(), 50);
+    assert!(p.checked_div(g).is_some());
+    assert!(q.checked_div(g).is_some());
+
+    // 実数の「-=」は実部だけ、「*=」は両方の成分を変える
+    let mut c = Complex { re: 10, im: 4 };
+    c -= 3;
+    assert_eq!(c, Complex { re: 7, im: 4 });
+    c *= 2;
+    assert_eq!(c, Complex { re: 14, im: 8 });
+    let mut f = Complex { re: 1.5, im: -0.5 };
+    f *= 2.0;
+    assert_eq!(f, Complex { re: 3.0, im: -1.0 });
+
+    // NaNや無限大を含むかどうか
+    let finite = Complex { re: 1.0, im: -2.0 };
+    let nan_im = Complex { re: 1.0, im: f64::NAN };
+    let inf_re = Complex { re: f64::INFINITY, im: 0.0 };
+    assert!(finite.is_finite() && !finite.is_nan());
+    assert!(!nan_im.is_finite() && nan_im.is_nan());
+    assert!(nan_im != nan_im);
+    assert!(!inf_re.is_finite() && !inf_re.is_nan());
+
+    // 複数の項からなる式の評価
+    assert_eq!(eval("3+4i - 1 + 2i"), Ok(Complex { re: 2.0, im: 6.0 }));
+    assert_eq!(eval("-i + 0.5 - 2.5 + 1e1i"), Ok(Complex { re: -2.0, im: 9.0 }));
+    assert_eq!(eval("7"), Ok(Complex { re: 7.0, im: 0.0 }));
+    assert_eq!(eval("3 + + 4i"), Err(ParseComplexError::InvalidNumber("+".to_string())));
+    assert_eq!(eval("1 + 2j"), Err(ParseComplexError::InvalidNumber("+2j".to_string())));
+    assert_eq!(eval("  "), Err(ParseComplexError::Empty));
+
+    // absはnormの別名
+    assert_eq!(Complex { re: 3.0f64, im: 4.0 }.abs(), 5.0);
+    assert_eq!(Complex { re: 3.0f32, im: 4.0 }.abs(), 5.0f32);
+
+    // 絶対値の大きさで並べ替える
+    let mut by_size = vec![MagOrd(Complex { re: 3.0, im: 4.0 }), MagOrd(Complex::i()),
+                           MagOrd(Complex { re: -2.0, im: 0.0 }), MagOrd(Complex::zero())];
+    by_size.sort();
+    let sorted: Vec<Complex<f64>> = by_size.into_iter().map(|MagOrd(c)| c).collect();
+    assert_eq!(sorted, vec![Complex::zero(), Complex::i(), Complex { re: -2.0, im: 0.0 },
+                            Complex { re: 3.0, im: 4.0 }]);
+    // 偏角が異なっても絶対値が等しければEqual
+    assert_eq!(MagOrd(Complex { re: 5.0, im: 0.0 }).cmp(&MagOrd(Complex { re: 0.0, im: -5.0 })),
+               Ordering::Equal);
+    // 絶対値の2乗がf64の範囲を超える値でも大小を区別できる
+    assert!(MagOrd(Complex { re: 1e200, im: 0.0 }) < MagOrd(Complex { re: 2e200, im: 0.0 }));
+    assert!(MagOrd(Complex { re: 1e-200, im: 0.0 }) != MagOrd(Complex::zero()));
+    // NaNは符号によらず最も大きい値として並ぶ
+    let inf = f64::INFINITY;
+    let mut with_nan = [MagOrd(Complex { re: inf - inf, im: 0.0 }), MagOrd(Complex { re: 0.0, im: -f64::NAN }),
+                        MagOrd(Complex { re: inf, im: 0.0 }), MagOrd(Complex::zero())];
+    with_nan.sort();
+    assert_eq!(with_nan[0].0, Complex::zero());
+    assert_eq!(with_nan[1].0, Complex { re: inf, im: 0.0 });
+    assert!(with_nan[2].0.is_nan() && with_nan[3].0.is_nan());
+
+    // 両方の成分を変換する
+    assert_eq!(Complex { re: 2.6, im: -1.4 }.map(f64::round), Complex { re: 3.0, im: -1.0 });
+    assert_eq!(Complex { re: 3, im: -4 }.map(|x| x * 2), Complex { re: 6, im: -8 });
+    assert_eq!(Complex { re: -3, im: 4 }.map(i32::abs), Complex { re: 3, im: 4 });
+
+    // 実数による割り算
+    assert_eq!(Complex { re: 10.0, im: 20.0 } / 2.0, Complex { re: 5.0, im: 10.0 });
+    // 整数の成分では切り捨てられる
+    assert_eq!(Complex { re: 7, im: -7 } / 2, Complex { re: 3, im: -3 });
+
     let s = "d\x6fv\x65t\x61i\x6c".to_string();
     let t = "\x64o\x76e\x74a\x69l".to_string();
     assert!(s == t); // 非Copy値であってもPartialEqは参照で借用されるだけ。所有権は移動しない
@@ -156,6 +1952,151 @@ fn main() {
     assert!(!(left < right));
     assert!(!(left >= right));
 
+    // 区間同士の加算は下限同士、上限同士を足す
+    assert_eq!(Interval { lower: 1, upper: 3 } + Interval { lower: 10, upper: 20 },
+               Interval { lower: 11, upper: 23 });
+    assert_eq!(Interval { lower: -0.5, upper: 0.5 } + Interval { lower: 1.0, upper: 2.0 },
+               Interval { lower: 0.5, upper: 2.5 });
+
+    // 区間が値を含むかどうか
+    let range = Interval { lower: 10, upper: 20 };
+    assert!(range.contains(&10));
+    assert!(range.contains(&15));
+    assert!(!range.contains(&20));
+    assert!(!range.contains(&9));
+    assert!(!range.contains(&25));
+
+    // 区間の共通部分
+    assert_eq!(Interval { lower: 1, upper: 5 }.intersection(Interval { lower: 3, upper: 9 }),
+               Some(Interval { lower: 3, upper: 5 }));
+    assert_eq!(Interval { lower: 1, upper: 5 }.intersection(Interval { lower: 7, upper: 9 }), None);
+    assert_eq!(Interval { lower: 1, upper: 5 }.intersection(Interval { lower: 5, upper: 9 }), None);
+    assert_eq!(Interval { lower: 0, upper: 10 }.intersection(Interval { lower: 2, upper: 4 }),
+               Some(Interval { lower: 2, upper: 4 }));
+
+    // 「&」は区間の共通部分
+    assert_eq!(Interval { lower: 1, upper: 5 } & Interval { lower: 3, upper: 9 },
+               Some(Interval { lower: 3, upper: 5 }));
+    assert_eq!(Interval { lower: 1, upper: 2 } & Interval { lower: 3, upper: 9 }, None);
+
+    // 区間の幅と空判定
+    assert_eq!(Interval { lower: 3, upper: 10 }.width(), 7);
+    assert!(!Interval { lower: 3, upper: 10 }.is_empty());
+    assert_eq!(Interval { lower: 5, upper: 5 }.width(), 0);
+    assert!(Interval { lower: 5, upper: 5 }.is_empty());
+    assert!(Interval { lower: 8, upper: 2 }.is_empty());
+
+    // 境界が浮動小数点数の区間も同じように順序比較できる
+    assert!(Interval { lower: 0.0, upper: 1.0 } < Interval { lower: 1.0, upper: 2.5 });
+    assert!(Interval { lower: 3.5, upper: 4.0 } > Interval { lower: 0.0, upper: 1.0 });
+    assert_eq!(Interval { lower: 0.5, upper: 1.5 }.partial_cmp(&Interval { lower: 0.5, upper: 1.5 }),
+               Some(Ordering::Equal));
+    assert_eq!(Interval { lower: 0.0, upper: 2.0 }.partial_cmp(&Interval { lower: 1.0, upper: 3.0 }),
+               None);
+    assert_eq!(Interval { lower: f64::NAN, upper: 1.0 }.partial_cmp(&Interval { lower: 0.0, upper: 1.0 }),
+               None);
+
+    // 半開区間の表示
+    assert_eq!(format!("{}", Interval { lower: 10, upper: 20 }), "[10, 20)");
+    assert_eq!(format!("{:.1}", Interval { lower: 0.21, upper: 1.0 }), "[0.2, 1.0)");
+
+    // 整数の区間はforループやcollectで反復できる
+    assert_eq!(Interval { lower: 0, upper: 3 }.into_iter().collect::<Vec<i32>>(), vec![0, 1, 2]);
+    assert_eq!(Interval { lower: 5u8, upper: 5 }.into_iter().count(), 0);
+    let mut total = 0;
+    let range = Interval { lower: 1, upper: 5 };
+    for x in range {
+        total += x;
+    }
+    assert_eq!(total, 10);
+
+    // スカラー値による平行移動
+    assert_eq!(Interval { lower: 1, upper: 5 } + 10, Interval { lower: 11, upper: 15 });
+    assert_eq!(Interval { lower: 1, upper: 5 } + -3, Interval { lower: -2, upper: 2 });
+    assert_eq!(Interval { lower: 1, upper: 5 } - 1, Interval { lower: 0, upper: 4 });
+    assert_eq!(Interval { lower: 1.0, upper: 2.0 } - -0.5, Interval { lower: 1.5, upper: 2.5 });
+
+    // 「%」は区間の差集合
+    assert_eq!(Interval { lower: 0, upper: 10 } % Interval { lower: 3, upper: 5 },
+               vec![Interval { lower: 0, upper: 3 }, Interval { lower: 5, upper: 10 }]);
+    assert_eq!(Interval { lower: 0, upper: 10 } % Interval { lower: 7, upper: 15 },
+               vec![Interval { lower: 0, upper: 7 }]);
+    assert_eq!(Interval { lower: 0, upper: 10 } % Interval { lower: 20, upper: 30 },
+               vec![Interval { lower: 0, upper: 10 }]);
+    assert_eq!(Interval { lower: 2, upper: 4 } % Interval { lower: 0, upper: 10 }, vec![]);
+
+    // 区間の範囲に値を収める
+    let range = Interval { lower: 10, upper: 20 };
+    assert_eq!(range.clamp(5), 10);
+    assert_eq!(range.clamp(15), 15);
+    assert_eq!(range.clamp(25), 20);
+
+    // 区間の中点
+    assert_eq!(Interval { lower: 0.0, upper: 10.0 }.midpoint(), 5.0);
+    assert_eq!(Interval { lower: -3.0, upper: 2.0 }.midpoint(), -0.5);
+
+    // 「|」は両方を含む最小の区間
+    assert_eq!(Interval { lower: 1, upper: 5 } | Interval { lower: 3, upper: 9 },
+               Interval { lower: 1, upper: 9 });
+    assert_eq!(Interval { lower: 1, upper: 3 } | Interval { lower: 3, upper: 6 },
+               Interval { lower: 1, upper: 6 });
+    // 離れた区間の隙間[3, 7)も含まれる
+    assert_eq!(Interval { lower: 7, upper: 9 } | Interval { lower: 1, upper: 3 },
+               Interval { lower: 1, upper: 9 });
+
+    // 区間が重なるかどうか
+    assert!(Interval { lower: 1, upper: 5 }.overlaps(&Interval { lower: 4, upper: 8 }));
+    assert!(!Interval { lower: 1, upper: 5 }.overlaps(&Interval { lower: 5, upper: 8 }));
+    assert!(!Interval { lower: 1, upper: 5 }.overlaps(&Interval { lower: 6, upper: 8 }));
+
+    // 予約済みの区間のどれとも重ならないかどうか
+    let booked = [Interval { lower: 0, upper: 3 }, Interval { lower: 8, upper: 10 }];
+    assert!(Interval { lower: 3, upper: 8 }.disjoint_from_all(&booked));
+    assert!(!Interval { lower: 2, upper: 5 }.disjoint_from_all(&booked));
+
+    // 重なる区間の併合
+    let mut ranges = vec![Interval { lower: 7, upper: 9 }, Interval { lower: 1, upper: 3 },
+                          Interval { lower: 2, upper: 5 }];
+    merge_all(&mut ranges);
+    assert_eq!(ranges, vec![Interval { lower: 1, upper: 5 }, Interval { lower: 7, upper: 9 }]);
+    // 隣接する区間もまとめられる
+    let mut ranges = vec![Interval { lower: 1, upper: 3 }, Interval { lower: 3, upper: 4 }];
+    merge_all(&mut ranges);
+    assert_eq!(ranges, vec![Interval { lower: 1, upper: 4 }]);
+    // 互いに素な区間はそのまま
+    let mut ranges = vec![Interval { lower: 0, upper: 1 }, Interval { lower: 5, upper: 6 }];
+    merge_all(&mut ranges);
+    assert_eq!(ranges, vec![Interval { lower: 0, upper: 1 }, Interval { lower: 5, upper: 6 }]);
+
+    // 区間の分割
+    assert_eq!(Interval { lower: 0, upper: 10 }.split_at(4),
+               (Some(Interval { lower: 0, upper: 4 }), Some(Interval { lower: 4, upper: 10 })));
+    assert_eq!(Interval { lower: 0, upper: 10 }.split_at(0),
+               (None, Some(Interval { lower: 0, upper: 10 })));
+    assert_eq!(Interval { lower: 0, upper: 10 }.split_at(15),
+               (Some(Interval { lower: 0, upper: 10 }), None));
+    assert_eq!(Interval { lower: 0, upper: 10 }.split_at(-5),
+               (None, Some(Interval { lower: 0, upper: 10 })));
+
+    // 凸包
+    assert_eq!(Interval { lower: 0, upper: 10 }.hull(Interval { lower: 2, upper: 4 }),
+               Interval { lower: 0, upper: 10 });
+    assert_eq!(Interval { lower: 0, upper: 5 }.hull(Interval { lower: 3, upper: 8 }),
+               Interval { lower: 0, upper: 8 });
+    assert_eq!(Interval { lower: 6, upper: 8 }.hull(Interval { lower: -2, upper: 1 }),
+               Interval { lower: -2, upper: 8 });
+
+    // 区間の拡大と縮小
+    assert_eq!(Interval { lower: 5, upper: 10 }.expand(2), Interval { lower: 3, upper: 12 });
+    assert_eq!(Interval { lower: 5, upper: 10 }.contract(2), Interval { lower: 7, upper: 8 });
+    let shrunk = Interval { lower: 5, upper: 6 }.contract(1);
+    assert!(shrunk.is_empty());
+    assert_eq!(shrunk.width(), 0);
+
+    // 等間隔の標本点
+    assert_eq!(Interval { lower: 0.0, upper: 10.0 }.sample(5), vec![0.0, 2.0, 4.0, 6.0, 8.0]);
+    assert_eq!(Interval { lower: 0.0, upper: 10.0 }.sample(0), Vec::<f64>::new());
+
     // HashMap<&str, i32>がIndex<&str>を実装しているためm[i]で参照できる
     let mut m = HashMap::new();
     m.insert("十", 10);
@@ -177,4 +2118,320 @@ fn main() {
     // 上のインデックス指定した文字列の追加は下のコードと等価
     (*desserts.index_mut(0)).push_str(" (fictional)");
     (*desserts.index_mut(1)).push_str(" (real)");
+
+    // Index/IndexMutを実装したImageはimage[row][column]で読み書きできる
+    let mut image = Image::<u8>::new(4, 3);
+    image[2][1] = 255;
+    assert_eq!(image[2][1], 255);
+    assert_eq!(image[0][0], 0);
+
+    // タプルの添字でもピクセルを読み書きできる
+    image[(1, 3)] = 128;
+    assert_eq!(image[(1, 3)], 128);
+    assert_eq!(image[1][3], 128);
+    assert_eq!(image[(2, 1)], image[2][1]);
+    // 幅を超える列は次の行のピクセルにならずパニックする
+    assert!(panics(|| image[(0, 4)]));
+    assert!(panics(|| image[0][4]));
+
+    // get/get_mutは範囲外の座標でNoneを返す
+    assert_eq!(image.get(1, 3), Some(&128));
+    assert_eq!(image.get(3, 0), None);
+    assert_eq!(image.get(0, 4), None);
+    if let Some(p) = image.get_mut(0, 0) {
+        *p = 1;
+    }
+    assert_eq!(image[(0, 0)], 1);
+    assert!(image.get_mut(0, 4).is_none());
+    assert!(image.get_mut(3, 0).is_none());
+    // 行の添字が大きすぎてもオーバーフローせずNoneになる
+    let mut numbered = Image::from_raw(4, (1..=12).collect::<Vec<u8>>());
+    assert_eq!(numbered.get(usize::MAX / 4 + 1, 1), None);
+    assert!(numbered.get_mut(usize::MAX / 4 + 1, 1).is_none());
+
+    // 幅と高さ
+    assert_eq!(image.width(), 4);
+    assert_eq!(image.height(), 3);
+    assert_eq!(Image::<u8>::new(0, 3).height(), 0);
+
+    // 同じ大きさの画像はピクセルごとに足し合わせられる
+    let mut a = Image::<u8>::new(2, 2);
+    let mut b = Image::<u8>::new(2, 2);
+    a[(0, 0)] = 10;
+    a[(1, 1)] = 20;
+    b[(0, 0)] = 5;
+    b[(0, 1)] = 7;
+    let sum = a + b;
+    assert_eq!(sum.pixels, vec![15, 7, 0, 20]);
+    // 大きさが異なる画像の加算はパニックする
+    assert!(panics(|| Image::<u8>::new(2, 2) + Image::<u8>::new(3, 2)));
+    assert!(panics(|| Image::<u8>::new(2, 2) + Image::<u8>::new(2, 3)));
+
+    // mapで全ピクセルを変換する
+    let mut src = Image::<i32>::new(3, 2);
+    src[(0, 1)] = 4;
+    src[(1, 2)] = -5;
+    let doubled = src.map(|&p| p * 2);
+    assert_eq!(doubled.pixels, vec![0, 8, 0, 0, 0, -10]);
+    assert_eq!((doubled.width(), doubled.height()), (src.width(), src.height()));
+    let spectrum: Image<Complex<f64>> = src.map(|&p| Complex::from(f64::from(p)));
+    assert_eq!(spectrum[(1, 2)], Complex { re: -5.0, im: 0.0 });
+
+    // from_fnで座標から各ピクセルを計算する
+    let gradient = Image::from_fn(4, 3, |r, c| r * 4 + c);
+    assert_eq!(gradient[(0, 0)], 0);
+    assert_eq!(gradient[(0, 3)], 3);
+    assert_eq!(gradient[(2, 0)], 8);
+    assert_eq!(gradient[(2, 3)], 11);
+    assert_eq!(gradient.height(), 3);
+
+    // rows/rows_mutで行ごとに走査する
+    let mut canvas = Image::<u8>::new(4, 3);
+    assert_eq!(canvas.rows().count(), 3);
+    assert!(canvas.rows().all(|row| row.len() == 4));
+    for p in canvas.rows_mut().last().unwrap() {
+        *p = 9;
+    }
+    assert_eq!(canvas[2], [9, 9, 9, 9]);
+    assert_eq!(canvas[1], [0, 0, 0, 0]);
+    assert_eq!(Image::<u8>::new(0, 3).rows().count(), 0);
+
+    // 転置すると幅と高さが入れ替わる
+    let wide = Image::from_fn(3, 2, |r, c| (r * 3 + c) as i32);
+    let tall = wide.transpose();
+    assert_eq!((tall.width(), tall.height()), (2, 3));
+    for r in 0..2 {
+        for c in 0..3 {
+            assert_eq!(tall[(c, r)], wide[(r, c)]);
+        }
+    }
+    assert_eq!(tall.pixels, vec![0, 3, 1, 4, 2, 5]);
+    assert_eq!(tall.transpose().pixels, wide.pixels);
+
+    // 左右反転と上下反転
+    let square = Image::from_fn(3, 3, |r, c| r * 3 + c);
+    let h = square.flip_horizontal();
+    assert_eq!((h[(0, 0)], h[(0, 2)], h[(2, 0)], h[(2, 2)]), (2, 0, 8, 6));
+    let v = square.flip_vertical();
+    assert_eq!((v[(0, 0)], v[(0, 2)], v[(2, 0)], v[(2, 2)]), (6, 8, 0, 2));
+    assert_eq!(h.flip_horizontal().pixels, square.pixels);
+    assert_eq!(v.flip_vertical().pixels, square.pixels);
+    assert_eq!(Image::<u8>::new(0, 0).flip_vertical().height(), 0);
+
+    // 中央の2x2を切り出す
+    let board = Image::from_fn(4, 4, |r, c| r * 4 + c);
+    let center = board.crop(1, 1, 2, 2);
+    assert_eq!((center.width(), center.height()), (2, 2));
+    assert_eq!(center.pixels, vec![5, 6, 9, 10]);
+    assert!(panics(|| board.crop(3, 3, 2, 2)));
+
+    // fillで全ピクセルを塗りつぶす
+    let mut plain = Image::<u8>::new(3, 2);
+    plain.fill(7);
+    assert_eq!(plain.pixels.len(), 6);
+    for r in 0..plain.height() {
+        for c in 0..plain.width() {
+            assert_eq!(plain[(r, c)], 7);
+        }
+    }
+
+    // PartialEqによる画像の比較
+    let base = Image::from_fn(3, 2, |r, c| r + c);
+    assert!(base == Image::from_fn(3, 2, |r, c| r + c));
+    let mut changed = Image::from_fn(3, 2, |r, c| r + c);
+    changed[(1, 2)] = 100;
+    assert!(base != changed);
+    assert!(Image::<u8>::new(3, 2) != Image::<u8>::new(2, 3));
+
+    // Cloneした画像を変更しても元の画像は変わらない
+    let original = Image::from_fn(2, 2, |r, c| (r * 2 + c) as u8);
+    let mut snapshot = original.clone();
+    snapshot[(0, 0)] = 99;
+    assert_eq!(original[(0, 0)], 0);
+    assert_ne!(original, snapshot);
+    assert_eq!(format!("{:?}", original), "Image { width: 2, pixels: [0, 1, 2, 3] }");
+
+    // PPM形式での書き出し
+    let gray = Image::from_fn(3, 2, |r, c| (r * 3 + c) as u8);
+    let pgm = gray.to_ppm();
+    assert!(pgm.starts_with(b"P5\n3 2\n255\n"));
+    assert_eq!(pgm.len(), b"P5\n3 2\n255\n".len() + 6);
+    assert_eq!(&pgm[pgm.len() - 6..], &[0, 1, 2, 3, 4, 5]);
+    let rgb = Image::from_fn(2, 1, |_, c| if c == 0 { (255, 0, 0) } else { (0, 0, 255) });
+    let ppm = rgb.to_ppm();
+    assert!(ppm.starts_with(b"P6\n2 1\n255\n"));
+    assert_eq!(&ppm[b"P6\n2 1\n255\n".len()..], &[255, 0, 0, 0, 0, 255]);
+
+    // 恒等カーネルの畳み込みは元の画像と等しい
+    let field = Image::from_fn(3, 3, |r, c| (r * 3 + c) as f64);
+    let identity = [0.0, 0.0, 0.0,
+                    0.0, 1.0, 0.0,
+                    0.0, 0.0, 0.0];
+    assert_eq!(field.convolve(&identity, 3), field);
+    // 平均化フィルタでは中央のピクセルが周囲9ピクセルの平均になる
+    let blurred = field.convolve(&[1.0 / 9.0; 9], 3);
+    assert!((blurred[(1, 1)] - 4.0).abs() < 1e-9);
+    // 端では外側の参照が端のピクセルにクランプされる
+    // (0, 0)の近傍は0, 0, 1, 0, 0, 1, 3, 3, 4
+    assert!((blurred[(0, 0)] - 12.0 / 9.0).abs() < 1e-9);
+
+    // 複素数の画像に変換して実部を取り出すと元の画像に戻る
+    let spectrum = field.to_complex();
+    assert_eq!(spectrum[(1, 2)], Complex { re: 5.0, im: 0.0 });
+    assert_eq!(spectrum.real_part(), field);
+
+    // スカラー倍で全ピクセルを定数倍する
+    let scaled = Image::from_fn(3, 2, |r, c| (r * 3 + c) as i32) * 3;
+    assert_eq!((scaled.width(), scaled.height()), (3, 2));
+    assert_eq!(scaled.pixels, vec![0, 3, 6, 9, 12, 15]);
+
+    // 符号反転
+    let signed = Image::from_fn(2, 3, |r, c| r as i32 - c as i32);
+    let negated = -signed.clone();
+    assert_eq!((negated.width(), negated.height()), (2, 3));
+    assert_eq!(negated.pixels, signed.pixels.iter().map(|p| -p).collect::<Vec<i32>>());
+    assert_eq!(negated[(2, 0)], -2);
+
+    // 列の走査
+    let grid = Image::from_fn(3, 3, |r, c| (r * 3 + c) as i32);
+    assert_eq!(grid.column(1).cloned().collect::<Vec<i32>>(), vec![1, 4, 7]);
+    assert_eq!(grid.column(2).count(), 3);
+    assert!(panics(|| grid.column(3).count()));
+    assert_eq!(Image::<u8>::new(3, 0).column(1).count(), 0);
+
+    // ピクセル列との相互変換
+    let raw: Vec<u8> = vec![1, 2, 3, 4, 5, 6];
+    let from_vec = Image::from_raw(3, raw.clone());
+    assert_eq!((from_vec.width(), from_vec.height()), (3, 2));
+    assert_eq!(from_vec[(1, 0)], 4);
+    assert_eq!(from_vec.into_raw(), raw);
+    assert!(panics(|| Image::from_raw(4, vec![0u8; 6])));
+    assert!(panics(|| Image::from_raw(0, vec![0u8; 6])));
+    assert_eq!(Image::<u8>::from_raw(0, vec![]).height(), 0);
+
+    // ピクセルごとの差分
+    let before = Image::from_fn(3, 2, |r, c| (r * 3 + c) as i32);
+    let after = Image::from_fn(3, 2, |r, c| (r * 3 + c) as i32 * 2);
+    assert_eq!((after - before.clone()).pixels, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(before.clone() - before, Image::<i32>::new(3, 2));
+    assert!(panics(|| Image::<i32>::new(2, 2) - Image::<i32>::new(2, 3)));
+
+    // 90度回転すると幅と高さが入れ替わる
+    // 0 1 2  時計回り  3 0  反時計回り  2 5
+    // 3 4 5  ------>  4 1  -------->  1 4
+    //                 5 2             0 3
+    let landscape = Image::from_fn(3, 2, |r, c| r * 3 + c);
+    assert_eq!(landscape.rotate90_cw(), Image::from_raw(2, vec![3, 0, 4, 1, 5, 2]));
+    assert_eq!(landscape.rotate90_ccw(), Image::from_raw(2, vec![2, 5, 1, 4, 0, 3]));
+    assert_eq!(landscape.rotate90_cw().rotate90_cw().rotate90_cw().rotate90_cw(), landscape);
+    assert_eq!(landscape.rotate90_cw().rotate90_ccw(), landscape);
+
+    // 別の画像を重ねて書き込む
+    let sprite = Image::from_fn(2, 2, |r, c| (r * 2 + c + 1) as u8);
+    let mut stage = Image::<u8>::new(4, 4);
+    stage.blit(&sprite, 1, 1);
+    assert_eq!(stage.pixels, vec![0, 0, 0, 0,
+                                  0, 1, 2, 0,
+                                  0, 3, 4, 0,
+                                  0, 0, 0, 0]);
+    // 右下にはみ出した部分は切り捨てられる
+    let mut edge = Image::<u8>::new(4, 4);
+    edge.blit(&sprite, 3, 2);
+    assert_eq!(edge.pixels, vec![0, 0, 0, 0,
+                                 0, 0, 0, 0,
+                                 0, 0, 0, 0,
+                                 0, 0, 1, 2]);
+    edge.blit(&sprite, 4, 4);
+    assert_eq!(edge.pixels.iter().filter(|&&p| p != 0).count(), 2);
+
+    // 範囲の添字で複数行をまとめて参照する
+    let mut striped = Image::from_fn(4, 3, |r, _| r as u8);
+    assert_eq!(striped[1..3].len(), 2 * striped.width());
+    assert_eq!(striped[1..3], [1, 1, 1, 1, 2, 2, 2, 2]);
+    for p in &mut striped[0..2] {
+        *p += 10;
+    }
+    assert_eq!(striped.pixels, vec![10, 10, 10, 10, 11, 11, 11, 11, 2, 2, 2, 2]);
+
+    // 輝度のヒストグラム
+    let mut flat = Image::<u8>::new(4, 3);
+    flat.fill(42);
+    let bins = flat.histogram();
+    assert_eq!(bins[42], 12);
+    assert_eq!(bins.iter().sum::<u32>(), 12);
+    let mixed = Image::from_raw(3, vec![0u8, 255, 7, 7, 0, 7]);
+    let bins = mixed.histogram();
+    assert_eq!((bins[0], bins[7], bins[255], bins[1]), (2, 3, 1, 0));
+
+    // 全ピクセルの走査
+    let mut counter = Image::from_fn(4, 3, |r, c| (r * 4 + c) as u8);
+    assert_eq!(counter.pixels().count(), counter.width() * counter.height());
+    for p in counter.pixels_mut() {
+        *p += 1;
+    }
+    assert_eq!(counter.pixels().cloned().collect::<Vec<u8>>(), (1..=12).collect::<Vec<u8>>());
+
+    // 座標付きの走査
+    let coords = Image::from_fn(3, 2, |r, c| r * 10 + c);
+    assert_eq!(coords.enumerate_pixels().count(), 6);
+    for (r, c, &p) in coords.enumerate_pixels() {
+        assert_eq!(p, coords[(r, c)]);
+        assert_eq!(p, r * 10 + c);
+    }
+    assert_eq!(coords.enumerate_pixels().last(), Some((1, 2, &12)));
+
+    // 最近傍法で拡大すると各ピクセルが2x2のブロックになる
+    let tiny = Image::from_raw(2, vec![1, 2, 3, 4]);
+    let big = tiny.resize_nearest(4, 4);
+    assert_eq!(big, Image::from_raw(4, vec![1, 1, 2, 2,
+                                           1, 1, 2, 2,
+                                           3, 3, 4, 4,
+                                           3, 3, 4, 4]));
+    assert_eq!(big.resize_nearest(2, 2), tiny);
+    // 空の画像は空の大きさにしか変えられない
+    assert_eq!(Image::<u8>::new(0, 0).resize_nearest(2, 0), Image::from_raw(2, vec![]));
+    assert!(panics(|| Image::<u8>::new(0, 0).resize_nearest(2, 2)));
+
+    // その場での変換はバッファを再確保しない
+    let mut brighten = Image::from_raw(3, vec![0u8, 10, 20, 30, 40, 50]);
+    let buffer = brighten.pixels.as_ptr();
+    brighten.apply_in_place(|p| *p += 1);
+    assert_eq!(brighten.pixels, vec![1, 11, 21, 31, 41, 51]);
+    assert_eq!(brighten.pixels.as_ptr(), buffer);
+
+    // 一部のピクセルだけを指定して画像を作る
+    let stars = Image::from_sparse(3, 3, vec![(0, 0, 5u8), (1, 2, 7), (2, 1, 9)]);
+    assert_eq!(stars.pixels, vec![5, 0, 0,
+                                  0, 0, 7,
+                                  0, 9, 0]);
+    assert_eq!(stars.pixels().filter(|&&p| p == u8::default()).count(), 6);
+    // 範囲外の座標は別のピクセルに書き込まずパニックする
+    assert!(panics(|| Image::from_sparse(3, 3, vec![(0, 5, 1u8)])));
+    assert!(panics(|| Image::from_sparse(3, 3, vec![(3, 0, 1u8)])));
+
+    // RGB画像のチャンネル分解と合成
+    let color = Image::from_fn(3, 2, |r, c| ((r * 3 + c) as u8, 100 + c as u8, 200 - r as u8));
+    let (red, green, blue) = color.split_channels();
+    assert_eq!(red.pixels, vec![0, 1, 2, 3, 4, 5]);
+    assert_eq!(green[(1, 2)], 102);
+    assert_eq!(blue[(1, 0)], 199);
+    assert_eq!(merge_channels(&red, &green, &blue), color);
+    assert!(panics(|| merge_channels(&red, &green, &Image::new(2, 3))));
+
+    // 周囲に枠を付ける
+    let inner = Image::from_raw(2, vec![1u8, 2, 3, 4]);
+    let framed = inner.pad(1, 1, 1, 1, 9);
+    assert_eq!(framed, Image::from_raw(4, vec![9, 9, 9, 9,
+                                              9, 1, 2, 9,
+                                              9, 3, 4, 9,
+                                              9, 9, 9, 9]));
+    assert_eq!(framed.crop(1, 1, 2, 2), inner);
+    let uneven = inner.pad(0, 2, 3, 0, 0);
+    assert_eq!((uneven.width(), uneven.height()), (5, 4));
+    assert_eq!(uneven[(0, 3)], 1);
+
+    // しきい値を超えるピクセルの数
+    let levels = Image::from_raw(3, vec![10u8, 200, 128, 129, 255, 0]);
+    assert_eq!(levels.count(|&p| p > 128), 3);
+    assert_eq!(levels.count(|_| false), 0);
 }